@@ -0,0 +1,187 @@
+use std::{
+    char::{REPLACEMENT_CHARACTER, decode_utf16},
+    fmt,
+    io::{self, Cursor},
+    marker::PhantomData,
+};
+
+use byteorder::{ByteOrder, LittleEndian};
+
+use crate::{DecodeLimits, Limited, PtpDeserialize, Read};
+
+pub trait PtpDeserializeBorrowed<'a>: Sized {
+    fn try_from_ptp_borrowed(buf: &'a [u8]) -> io::Result<Self> {
+        Self::try_from_ptp_borrowed_with_limits(buf, DecodeLimits::default())
+    }
+
+    fn try_from_ptp_borrowed_with_limits(buf: &'a [u8], limits: DecodeLimits) -> io::Result<Self> {
+        let mut cur = Limited::new(Cursor::new(buf), limits);
+        let val = Self::try_read_ptp_borrowed(&mut cur)?;
+        cur.expect_end()?;
+        Ok(val)
+    }
+
+    fn try_read_ptp_borrowed(cur: &mut BorrowedCursor<'a>) -> io::Result<Self>;
+}
+
+pub type BorrowedCursor<'a> = Limited<Cursor<&'a [u8]>>;
+
+pub trait PtpFixedSize: PtpDeserialize + Copy {
+    const SIZE: usize;
+}
+
+macro_rules! ptp_fixed_size {
+    ($($ty:ty),*) => {
+        $(impl PtpFixedSize for $ty {
+            const SIZE: usize = size_of::<$ty>();
+        })*
+    };
+}
+
+ptp_fixed_size!(u8, i8, u16, i16, u32, i32, u64, i64, u128, i128);
+
+fn take<'a>(cur: &mut BorrowedCursor<'a>, len: usize) -> io::Result<&'a [u8]> {
+    let cur = cur.get_mut();
+    let buf: &'a [u8] = cur.get_ref();
+    let start = usize::try_from(cur.position()).unwrap_or(usize::MAX);
+    let end = start
+        .checked_add(len)
+        .filter(|end| *end <= buf.len())
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!("Expected {len} bytes at offset {start}"),
+            )
+        })?;
+
+    cur.set_position(end as u64);
+    Ok(&buf[start..end])
+}
+
+impl<'a> PtpDeserializeBorrowed<'a> for &'a [u8] {
+    fn try_read_ptp_borrowed(cur: &mut BorrowedCursor<'a>) -> io::Result<Self> {
+        let len = cur.read_ptp_u32()? as usize;
+        cur.limits().check_array_len(len)?;
+        take(cur, len)
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct PtpArray<'a, T: PtpFixedSize> {
+    data: &'a [u8],
+    _marker: PhantomData<T>,
+}
+
+impl<'a, T: PtpFixedSize> PtpArray<'a, T> {
+    pub const fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    pub const fn len(&self) -> usize {
+        self.data.len() / T::SIZE
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<T> {
+        let start = index.checked_mul(T::SIZE)?;
+        let bytes = self.data.get(start..start.checked_add(T::SIZE)?)?;
+        T::try_from_ptp(bytes).ok()
+    }
+
+    pub fn first(&self) -> Option<T> {
+        self.get(0)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = T> + 'a {
+        self.data
+            .chunks_exact(T::SIZE)
+            .filter_map(|bytes| T::try_from_ptp(bytes).ok())
+    }
+
+    pub fn to_vec(&self) -> Vec<T> {
+        self.iter().collect()
+    }
+}
+
+impl<'a, T: PtpFixedSize> PtpDeserializeBorrowed<'a> for PtpArray<'a, T> {
+    fn try_read_ptp_borrowed(cur: &mut BorrowedCursor<'a>) -> io::Result<Self> {
+        let len = cur.read_ptp_u32()? as usize;
+        cur.limits().check_array_len(len)?;
+
+        let width = len
+            .checked_mul(T::SIZE)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Array length overflow"))?;
+
+        Ok(Self {
+            data: take(cur, width)?,
+            _marker: PhantomData,
+        })
+    }
+}
+
+impl<T: PtpFixedSize + fmt::Debug> fmt::Debug for PtpArray<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct PtpStr<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> PtpStr<'a> {
+    pub const fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn units(&self) -> impl Iterator<Item = u16> + 'a {
+        self.data.chunks_exact(2).map(LittleEndian::read_u16)
+    }
+
+    pub fn to_string_lossy(&self) -> String {
+        decode_utf16(self.units())
+            .map(|c| c.unwrap_or(REPLACEMENT_CHARACTER))
+            .collect()
+    }
+
+    pub fn try_to_string(&self) -> io::Result<String> {
+        decode_utf16(self.units())
+            .collect::<Result<String, _>>()
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Invalid UTF-16"))
+    }
+}
+
+impl<'a> PtpDeserializeBorrowed<'a> for PtpStr<'a> {
+    fn try_read_ptp_borrowed(cur: &mut BorrowedCursor<'a>) -> io::Result<Self> {
+        let len = cur.read_ptp_u8()? as usize;
+        if len == 0 {
+            return Ok(Self::default());
+        }
+        cur.limits().check_string_len(len)?;
+
+        let data = take(cur, (len - 1) * 2)?;
+        take(cur, 2)?;
+
+        Ok(Self { data })
+    }
+}
+
+impl fmt::Display for PtpStr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_string_lossy())
+    }
+}
+
+impl fmt::Debug for PtpStr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.to_string_lossy(), f)
+    }
+}
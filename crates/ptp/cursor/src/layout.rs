@@ -0,0 +1,228 @@
+use std::{
+    fmt::{Debug, Write as _},
+    io::{self, Cursor},
+};
+
+use crate::PtpDeserialize;
+
+const BYTES_PER_LINE: usize = 16;
+const COLLAPSE_THRESHOLD: usize = 2 * BYTES_PER_LINE;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutKind {
+    Field,
+    Unknown,
+    Padding,
+    Unmapped,
+}
+
+impl LayoutKind {
+    const fn marker(self) -> &'static str {
+        match self {
+            Self::Field => "  ",
+            Self::Unknown => "??",
+            Self::Padding => "..",
+            Self::Unmapped => "!!",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LayoutField {
+    pub name: String,
+    pub offset: usize,
+    pub width: usize,
+    pub value: String,
+    pub kind: LayoutKind,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Layout {
+    pub fields: Vec<LayoutField>,
+    pub len: usize,
+    pub error: Option<String>,
+}
+
+pub trait PtpLayout {
+    fn read_ptp_layout(reader: &mut LayoutReader<'_>) -> io::Result<()>;
+
+    fn ptp_layout(buf: &[u8]) -> Layout
+    where
+        Self: Sized,
+    {
+        let mut reader = LayoutReader::new(buf);
+        let result = Self::read_ptp_layout(&mut reader);
+        reader.finish(result.err())
+    }
+}
+
+pub struct LayoutReader<'a> {
+    cur: Cursor<&'a [u8]>,
+    fields: Vec<LayoutField>,
+}
+
+impl<'a> LayoutReader<'a> {
+    pub const fn new(buf: &'a [u8]) -> Self {
+        Self {
+            cur: Cursor::new(buf),
+            fields: Vec::new(),
+        }
+    }
+
+    pub fn cursor(&mut self) -> &mut Cursor<&'a [u8]> {
+        &mut self.cur
+    }
+
+    pub fn field<T: PtpDeserialize + Debug>(&mut self, name: &str) -> io::Result<T> {
+        self.record(name, LayoutKind::Field, |value: &T| format!("{value:?}"))
+    }
+
+    pub fn unknown<T: PtpDeserialize + Debug>(&mut self, name: &str) -> io::Result<T> {
+        self.record(name, LayoutKind::Unknown, |value: &T| format!("{value:?}"))
+    }
+
+    pub fn field_as<T, U>(&mut self, name: &str) -> io::Result<T>
+    where
+        T: PtpDeserialize + Debug + Copy,
+        U: TryFrom<T> + Debug,
+    {
+        self.record(name, LayoutKind::Field, |value: &T| {
+            U::try_from(*value).map_or_else(
+                |_| format!("{value:?} (invalid)"),
+                |decoded| format!("{decoded:?} ({value:?})"),
+            )
+        })
+    }
+
+    pub fn padding(&mut self, name: &str, len: usize) -> io::Result<()> {
+        let offset = self.position();
+        let mut buf = vec![0u8; len];
+        io::Read::read_exact(&mut self.cur, &mut buf)?;
+
+        let value = if buf.iter().all(|b| *b == 0) {
+            String::from("zeroed")
+        } else {
+            String::from("non-zero")
+        };
+
+        self.fields.push(LayoutField {
+            name: name.to_string(),
+            offset,
+            width: len,
+            value,
+            kind: LayoutKind::Padding,
+        });
+
+        Ok(())
+    }
+
+    pub fn finish(self, error: Option<io::Error>) -> Layout {
+        let len = self.cur.get_ref().len();
+        let mut fields = self.fields;
+
+        let end = fields.last().map_or(0, |f| f.offset + f.width);
+        if end < len {
+            fields.push(LayoutField {
+                name: String::from("<unmapped>"),
+                offset: end,
+                width: len - end,
+                value: String::new(),
+                kind: LayoutKind::Unmapped,
+            });
+        }
+
+        Layout {
+            fields,
+            len,
+            error: error.map(|e| e.to_string()),
+        }
+    }
+
+    fn position(&self) -> usize {
+        usize::try_from(self.cur.position()).unwrap_or(usize::MAX)
+    }
+
+    fn record<T, F>(&mut self, name: &str, kind: LayoutKind, describe: F) -> io::Result<T>
+    where
+        T: PtpDeserialize,
+        F: FnOnce(&T) -> String,
+    {
+        let offset = self.position();
+        let value = T::try_read_ptp(&mut self.cur)?;
+        let width = self.position() - offset;
+
+        self.fields.push(LayoutField {
+            name: name.to_string(),
+            offset,
+            width,
+            value: describe(&value),
+            kind,
+        });
+
+        Ok(value)
+    }
+}
+
+// Unknown regions are marked with `??`, padding with `..` and bytes not covered by the layout
+// with `!!`. Long runs of identical bytes are collapsed into a single line.
+pub fn annotated_hex_dump(buf: &[u8], layout: &Layout) -> String {
+    let mut out = String::new();
+
+    for field in &layout.fields {
+        let start = field.offset.min(buf.len());
+        let end = (field.offset + field.width).min(buf.len());
+        let bytes = &buf[start..end];
+
+        let label = if field.value.is_empty() {
+            field.name.clone()
+        } else {
+            format!("{} = {}", field.name, field.value)
+        };
+
+        let collapsible = bytes.len() > COLLAPSE_THRESHOLD && bytes.iter().all(|b| *b == bytes[0]);
+        if collapsible {
+            let _ = writeln!(
+                out,
+                "{} 0x{start:04x}  {:<width$}  {label}",
+                field.kind.marker(),
+                format!("{:02x} x {}", bytes[0], bytes.len()),
+                width = BYTES_PER_LINE * 3 - 1,
+            );
+            continue;
+        }
+
+        if bytes.is_empty() {
+            let _ = writeln!(
+                out,
+                "{} 0x{start:04x}  {:<width$}  {label}",
+                field.kind.marker(),
+                "",
+                width = BYTES_PER_LINE * 3 - 1,
+            );
+            continue;
+        }
+
+        for (i, chunk) in bytes.chunks(BYTES_PER_LINE).enumerate() {
+            let hex = chunk
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            let annotation = if i == 0 { label.as_str() } else { "" };
+            let _ = writeln!(
+                out,
+                "{} 0x{:04x}  {hex:<width$}  {annotation}",
+                field.kind.marker(),
+                start + i * BYTES_PER_LINE,
+                width = BYTES_PER_LINE * 3 - 1,
+            );
+        }
+    }
+
+    if let Some(error) = &layout.error {
+        let _ = writeln!(out, "!! decoding stopped: {error}");
+    }
+
+    out
+}
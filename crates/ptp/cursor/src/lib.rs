@@ -1,14 +1,24 @@
 #![allow(dead_code)]
 #![allow(clippy::redundant_closure_for_method_calls)]
 
+mod borrowed;
+mod layout;
+mod limits;
 mod types;
 
+pub use borrowed::*;
+pub use layout::*;
+pub use limits::*;
 pub use types::*;
 
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use std::io::{self, Cursor};
 
 pub trait Read: ReadBytesExt {
+    fn limits(&self) -> DecodeLimits {
+        DecodeLimits::default()
+    }
+
     fn read_ptp_u8(&mut self) -> io::Result<u8> {
         self.read_u8()
     }
@@ -41,11 +51,20 @@ pub trait Read: ReadBytesExt {
         self.read_i64::<LittleEndian>()
     }
 
+    fn read_ptp_u128(&mut self) -> io::Result<u128> {
+        self.read_u128::<LittleEndian>()
+    }
+
+    fn read_ptp_i128(&mut self) -> io::Result<i128> {
+        self.read_i128::<LittleEndian>()
+    }
+
     fn read_ptp_vec<T, F>(&mut self, func: F) -> io::Result<Vec<T>>
     where
         F: Fn(&mut Self) -> io::Result<T>,
     {
         let len = self.read_u32::<LittleEndian>()? as usize;
+        self.limits().check_array_len(len)?;
         (0..len).map(|_| func(self)).collect()
     }
 
@@ -81,11 +100,20 @@ pub trait Read: ReadBytesExt {
         self.read_ptp_vec(|cur| cur.read_ptp_i64())
     }
 
+    fn read_ptp_u128_vec(&mut self) -> io::Result<Vec<u128>> {
+        self.read_ptp_vec(|cur| cur.read_ptp_u128())
+    }
+
+    fn read_ptp_i128_vec(&mut self) -> io::Result<Vec<i128>> {
+        self.read_ptp_vec(|cur| cur.read_ptp_i128())
+    }
+
     fn read_ptp_str(&mut self) -> io::Result<String> {
         let len = self.read_u8()?;
         if len == 0 {
             return Ok(String::new());
         }
+        self.limits().check_string_len(len as usize)?;
 
         let data: Vec<u16> = (0..(len - 1))
             .map(|_| self.read_u16::<LittleEndian>())
@@ -101,6 +129,7 @@ pub trait Read: ReadBytesExt {
         if len == 0 {
             return Ok(ExactString::new(String::new()));
         }
+        self.limits().check_string_len(len as usize)?;
 
         // For strings that do not include a null terminator
         let data: Vec<u16> = (0..len)
@@ -165,6 +194,14 @@ pub trait Write: WriteBytesExt {
         self.write_i64::<LittleEndian>(*v)
     }
 
+    fn write_ptp_u128(&mut self, v: &u128) -> io::Result<()> {
+        self.write_u128::<LittleEndian>(*v)
+    }
+
+    fn write_ptp_i128(&mut self, v: &i128) -> io::Result<()> {
+        self.write_i128::<LittleEndian>(*v)
+    }
+
     fn write_ptp_vec<T, F>(&mut self, vec: &[T], func: F) -> io::Result<()>
     where
         F: Fn(&mut Self, &T) -> io::Result<()>,
@@ -208,6 +245,14 @@ pub trait Write: WriteBytesExt {
         self.write_ptp_vec(vec, |cur, v| cur.write_ptp_i64(v))
     }
 
+    fn write_ptp_u128_vec(&mut self, vec: &[u128]) -> io::Result<()> {
+        self.write_ptp_vec(vec, |cur, v| cur.write_ptp_u128(v))
+    }
+
+    fn write_ptp_i128_vec(&mut self, vec: &[i128]) -> io::Result<()> {
+        self.write_ptp_vec(vec, |cur, v| cur.write_ptp_i128(v))
+    }
+
     fn write_ptp_str(&mut self, s: &str) -> io::Result<()> {
         if s.is_empty() {
             return self.write_u8(0);
@@ -237,30 +282,35 @@ pub trait Write: WriteBytesExt {
     }
 }
 
-impl Write for Vec<u8> {}
+impl<W: io::Write + ?Sized> Write for W {}
 
 pub trait PtpSerialize {
-    fn try_into_ptp(&self) -> io::Result<Vec<u8>>;
+    fn try_into_ptp(&self) -> io::Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        self.try_write_ptp(&mut buf)?;
+        Ok(buf)
+    }
 
-    fn try_write_ptp(&self, buf: &mut Vec<u8>) -> io::Result<()>;
+    fn try_write_ptp<W: Write + ?Sized>(&self, buf: &mut W) -> io::Result<()>;
 }
 
 pub trait PtpDeserialize: Sized {
     fn try_from_ptp(buf: &[u8]) -> io::Result<Self>;
 
     fn try_read_ptp<R: Read>(cur: &mut R) -> io::Result<Self>;
+
+    fn try_from_ptp_with_limits(buf: &[u8], limits: DecodeLimits) -> io::Result<Self> {
+        let mut cur = Limited::new(Cursor::new(buf), limits);
+        let val = Self::try_read_ptp(&mut cur)?;
+        cur.expect_end()?;
+        Ok(val)
+    }
 }
 
 macro_rules! ptp_ser {
     ($ty:ty, $write_fn:ident) => {
         impl PtpSerialize for $ty {
-            fn try_into_ptp(&self) -> io::Result<Vec<u8>> {
-                let mut buf = Vec::new();
-                self.try_write_ptp(&mut buf)?;
-                Ok(buf)
-            }
-
-            fn try_write_ptp(&self, buf: &mut Vec<u8>) -> io::Result<()> {
+            fn try_write_ptp<W: Write + ?Sized>(&self, buf: &mut W) -> io::Result<()> {
                 buf.$write_fn(self)
             }
         }
@@ -300,6 +350,10 @@ ptp_ser!(u64, write_ptp_u64);
 ptp_de!(u64, read_ptp_u64);
 ptp_ser!(i64, write_ptp_i64);
 ptp_de!(i64, read_ptp_i64);
+ptp_ser!(u128, write_ptp_u128);
+ptp_de!(u128, read_ptp_u128);
+ptp_ser!(i128, write_ptp_i128);
+ptp_de!(i128, read_ptp_i128);
 ptp_ser!(&str, write_ptp_str);
 ptp_ser!(String, write_ptp_str);
 ptp_de!(String, read_ptp_str);
@@ -321,3 +375,7 @@ ptp_ser!(Vec<u64>, write_ptp_u64_vec);
 ptp_de!(Vec<u64>, read_ptp_u64_vec);
 ptp_ser!(Vec<i64>, write_ptp_i64_vec);
 ptp_de!(Vec<i64>, read_ptp_i64_vec);
+ptp_ser!(Vec<u128>, write_ptp_u128_vec);
+ptp_de!(Vec<u128>, read_ptp_u128_vec);
+ptp_ser!(Vec<i128>, write_ptp_i128_vec);
+ptp_de!(Vec<i128>, read_ptp_i128_vec);
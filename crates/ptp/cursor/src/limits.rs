@@ -0,0 +1,85 @@
+use std::io;
+
+use crate::Read;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeLimits {
+    pub max_array_len: usize,
+    pub max_string_len: usize,
+    pub max_container_size: usize,
+}
+
+impl DecodeLimits {
+    pub const DEFAULT: Self = Self {
+        max_array_len: 1 << 20,
+        max_string_len: u8::MAX as usize,
+        max_container_size: 256 * 1024 * 1024,
+    };
+
+    pub fn check_array_len(&self, len: usize) -> io::Result<()> {
+        check("Array length", len, self.max_array_len)
+    }
+
+    pub fn check_string_len(&self, len: usize) -> io::Result<()> {
+        check("String length", len, self.max_string_len)
+    }
+
+    pub fn check_container_size(&self, len: usize) -> io::Result<()> {
+        check("Container size", len, self.max_container_size)
+    }
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+fn check(what: &str, len: usize, max: usize) -> io::Result<()> {
+    if len > max {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{what} {len} exceeds limit of {max}"),
+        ));
+    }
+    Ok(())
+}
+
+pub fn with_context(error: io::Error, context: &str) -> io::Error {
+    io::Error::new(error.kind(), format!("{context}: {error}"))
+}
+
+pub struct Limited<R> {
+    inner: R,
+    limits: DecodeLimits,
+}
+
+impl<R> Limited<R> {
+    pub const fn new(inner: R, limits: DecodeLimits) -> Self {
+        Self { inner, limits }
+    }
+
+    pub const fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: io::Read> io::Read for Limited<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl<R: Read> Read for Limited<R> {
+    fn limits(&self) -> DecodeLimits {
+        self.limits
+    }
+
+    fn expect_end(&mut self) -> io::Result<()> {
+        self.inner.expect_end()
+    }
+}
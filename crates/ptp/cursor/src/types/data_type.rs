@@ -0,0 +1,135 @@
+use std::{
+    fmt,
+    io::{self, Cursor},
+};
+
+use crate::{PtpDeserialize, PtpSerialize, Read, Write};
+
+#[repr(u16)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DataType {
+    Undefined = 0x0000,
+    Int8 = 0x0001,
+    Uint8 = 0x0002,
+    Int16 = 0x0003,
+    Uint16 = 0x0004,
+    Int32 = 0x0005,
+    Uint32 = 0x0006,
+    Int64 = 0x0007,
+    Uint64 = 0x0008,
+    Int128 = 0x0009,
+    Uint128 = 0x000A,
+    ArrayInt8 = 0x4001,
+    ArrayUint8 = 0x4002,
+    ArrayInt16 = 0x4003,
+    ArrayUint16 = 0x4004,
+    ArrayInt32 = 0x4005,
+    ArrayUint32 = 0x4006,
+    ArrayInt64 = 0x4007,
+    ArrayUint64 = 0x4008,
+    ArrayInt128 = 0x4009,
+    ArrayUint128 = 0x400A,
+    Str = 0xFFFF,
+}
+
+impl DataType {
+    const ALL: [Self; 22] = [
+        Self::Undefined,
+        Self::Int8,
+        Self::Uint8,
+        Self::Int16,
+        Self::Uint16,
+        Self::Int32,
+        Self::Uint32,
+        Self::Int64,
+        Self::Uint64,
+        Self::Int128,
+        Self::Uint128,
+        Self::ArrayInt8,
+        Self::ArrayUint8,
+        Self::ArrayInt16,
+        Self::ArrayUint16,
+        Self::ArrayInt32,
+        Self::ArrayUint32,
+        Self::ArrayInt64,
+        Self::ArrayUint64,
+        Self::ArrayInt128,
+        Self::ArrayUint128,
+        Self::Str,
+    ];
+
+    pub const fn is_array(self) -> bool {
+        (self as u16) & 0x4000 != 0 && !matches!(self, Self::Str)
+    }
+}
+
+impl From<DataType> for u16 {
+    fn from(value: DataType) -> Self {
+        value as Self
+    }
+}
+
+impl TryFrom<u16> for DataType {
+    type Error = io::Error;
+
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        Self::ALL
+            .into_iter()
+            .find(|t| *t as u16 == value)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Unknown data type 0x{value:04x}"),
+                )
+            })
+    }
+}
+
+impl fmt::Display for DataType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Undefined => "UNDEF",
+            Self::Int8 => "INT8",
+            Self::Uint8 => "UINT8",
+            Self::Int16 => "INT16",
+            Self::Uint16 => "UINT16",
+            Self::Int32 => "INT32",
+            Self::Uint32 => "UINT32",
+            Self::Int64 => "INT64",
+            Self::Uint64 => "UINT64",
+            Self::Int128 => "INT128",
+            Self::Uint128 => "UINT128",
+            Self::ArrayInt8 => "AINT8",
+            Self::ArrayUint8 => "AUINT8",
+            Self::ArrayInt16 => "AINT16",
+            Self::ArrayUint16 => "AUINT16",
+            Self::ArrayInt32 => "AINT32",
+            Self::ArrayUint32 => "AUINT32",
+            Self::ArrayInt64 => "AINT64",
+            Self::ArrayUint64 => "AUINT64",
+            Self::ArrayInt128 => "AINT128",
+            Self::ArrayUint128 => "AUINT128",
+            Self::Str => "STR",
+        };
+        f.write_str(name)
+    }
+}
+
+impl PtpSerialize for DataType {
+    fn try_write_ptp<W: Write + ?Sized>(&self, buf: &mut W) -> io::Result<()> {
+        buf.write_ptp_u16(&u16::from(*self))
+    }
+}
+
+impl PtpDeserialize for DataType {
+    fn try_from_ptp(buf: &[u8]) -> io::Result<Self> {
+        let mut cur = Cursor::new(buf);
+        let val = Self::try_read_ptp(&mut cur)?;
+        cur.expect_end()?;
+        Ok(val)
+    }
+
+    fn try_read_ptp<R: Read>(cur: &mut R) -> io::Result<Self> {
+        Self::try_from(cur.read_ptp_u16()?)
+    }
+}
@@ -1,3 +1,7 @@
+mod data_type;
 mod exact_string;
+mod value;
 
+pub use data_type::*;
 pub use exact_string::*;
+pub use value::*;
@@ -0,0 +1,164 @@
+use std::{
+    fmt,
+    io::{self, Cursor},
+};
+
+use crate::{DataType, PtpSerialize, Read, Write};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PtpValue {
+    Undefined,
+    Int8(i8),
+    Uint8(u8),
+    Int16(i16),
+    Uint16(u16),
+    Int32(i32),
+    Uint32(u32),
+    Int64(i64),
+    Uint64(u64),
+    Int128(i128),
+    Uint128(u128),
+    ArrayInt8(Vec<i8>),
+    ArrayUint8(Vec<u8>),
+    ArrayInt16(Vec<i16>),
+    ArrayUint16(Vec<u16>),
+    ArrayInt32(Vec<i32>),
+    ArrayUint32(Vec<u32>),
+    ArrayInt64(Vec<i64>),
+    ArrayUint64(Vec<u64>),
+    ArrayInt128(Vec<i128>),
+    ArrayUint128(Vec<u128>),
+    Str(String),
+}
+
+impl PtpValue {
+    pub const fn data_type(&self) -> DataType {
+        match self {
+            Self::Undefined => DataType::Undefined,
+            Self::Int8(_) => DataType::Int8,
+            Self::Uint8(_) => DataType::Uint8,
+            Self::Int16(_) => DataType::Int16,
+            Self::Uint16(_) => DataType::Uint16,
+            Self::Int32(_) => DataType::Int32,
+            Self::Uint32(_) => DataType::Uint32,
+            Self::Int64(_) => DataType::Int64,
+            Self::Uint64(_) => DataType::Uint64,
+            Self::Int128(_) => DataType::Int128,
+            Self::Uint128(_) => DataType::Uint128,
+            Self::ArrayInt8(_) => DataType::ArrayInt8,
+            Self::ArrayUint8(_) => DataType::ArrayUint8,
+            Self::ArrayInt16(_) => DataType::ArrayInt16,
+            Self::ArrayUint16(_) => DataType::ArrayUint16,
+            Self::ArrayInt32(_) => DataType::ArrayInt32,
+            Self::ArrayUint32(_) => DataType::ArrayUint32,
+            Self::ArrayInt64(_) => DataType::ArrayInt64,
+            Self::ArrayUint64(_) => DataType::ArrayUint64,
+            Self::ArrayInt128(_) => DataType::ArrayInt128,
+            Self::ArrayUint128(_) => DataType::ArrayUint128,
+            Self::Str(_) => DataType::Str,
+        }
+    }
+
+    pub fn try_from_ptp_as(buf: &[u8], data_type: DataType) -> io::Result<Self> {
+        let mut cur = Cursor::new(buf);
+        let val = Self::try_read_ptp_as(&mut cur, data_type)?;
+        cur.expect_end()?;
+        Ok(val)
+    }
+
+    pub fn try_read_ptp_as<R: Read>(cur: &mut R, data_type: DataType) -> io::Result<Self> {
+        Ok(match data_type {
+            DataType::Undefined => Self::Undefined,
+            DataType::Int8 => Self::Int8(cur.read_ptp_i8()?),
+            DataType::Uint8 => Self::Uint8(cur.read_ptp_u8()?),
+            DataType::Int16 => Self::Int16(cur.read_ptp_i16()?),
+            DataType::Uint16 => Self::Uint16(cur.read_ptp_u16()?),
+            DataType::Int32 => Self::Int32(cur.read_ptp_i32()?),
+            DataType::Uint32 => Self::Uint32(cur.read_ptp_u32()?),
+            DataType::Int64 => Self::Int64(cur.read_ptp_i64()?),
+            DataType::Uint64 => Self::Uint64(cur.read_ptp_u64()?),
+            DataType::Int128 => Self::Int128(cur.read_ptp_i128()?),
+            DataType::Uint128 => Self::Uint128(cur.read_ptp_u128()?),
+            DataType::ArrayInt8 => Self::ArrayInt8(cur.read_ptp_i8_vec()?),
+            DataType::ArrayUint8 => Self::ArrayUint8(cur.read_ptp_u8_vec()?),
+            DataType::ArrayInt16 => Self::ArrayInt16(cur.read_ptp_i16_vec()?),
+            DataType::ArrayUint16 => Self::ArrayUint16(cur.read_ptp_u16_vec()?),
+            DataType::ArrayInt32 => Self::ArrayInt32(cur.read_ptp_i32_vec()?),
+            DataType::ArrayUint32 => Self::ArrayUint32(cur.read_ptp_u32_vec()?),
+            DataType::ArrayInt64 => Self::ArrayInt64(cur.read_ptp_i64_vec()?),
+            DataType::ArrayUint64 => Self::ArrayUint64(cur.read_ptp_u64_vec()?),
+            DataType::ArrayInt128 => Self::ArrayInt128(cur.read_ptp_i128_vec()?),
+            DataType::ArrayUint128 => Self::ArrayUint128(cur.read_ptp_u128_vec()?),
+            DataType::Str => Self::Str(cur.read_ptp_str()?),
+        })
+    }
+}
+
+impl PtpSerialize for PtpValue {
+    fn try_write_ptp<W: Write + ?Sized>(&self, buf: &mut W) -> io::Result<()> {
+        match self {
+            Self::Undefined => Ok(()),
+            Self::Int8(v) => buf.write_ptp_i8(v),
+            Self::Uint8(v) => buf.write_ptp_u8(v),
+            Self::Int16(v) => buf.write_ptp_i16(v),
+            Self::Uint16(v) => buf.write_ptp_u16(v),
+            Self::Int32(v) => buf.write_ptp_i32(v),
+            Self::Uint32(v) => buf.write_ptp_u32(v),
+            Self::Int64(v) => buf.write_ptp_i64(v),
+            Self::Uint64(v) => buf.write_ptp_u64(v),
+            Self::Int128(v) => buf.write_ptp_i128(v),
+            Self::Uint128(v) => buf.write_ptp_u128(v),
+            Self::ArrayInt8(v) => buf.write_ptp_i8_vec(v),
+            Self::ArrayUint8(v) => buf.write_ptp_u8_vec(v),
+            Self::ArrayInt16(v) => buf.write_ptp_i16_vec(v),
+            Self::ArrayUint16(v) => buf.write_ptp_u16_vec(v),
+            Self::ArrayInt32(v) => buf.write_ptp_i32_vec(v),
+            Self::ArrayUint32(v) => buf.write_ptp_u32_vec(v),
+            Self::ArrayInt64(v) => buf.write_ptp_i64_vec(v),
+            Self::ArrayUint64(v) => buf.write_ptp_u64_vec(v),
+            Self::ArrayInt128(v) => buf.write_ptp_i128_vec(v),
+            Self::ArrayUint128(v) => buf.write_ptp_u128_vec(v),
+            Self::Str(v) => buf.write_ptp_str(v),
+        }
+    }
+}
+
+fn fmt_array<T: fmt::Display>(f: &mut fmt::Formatter<'_>, values: &[T]) -> fmt::Result {
+    f.write_str("[")?;
+    for (i, v) in values.iter().enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        write!(f, "{v}")?;
+    }
+    f.write_str("]")
+}
+
+impl fmt::Display for PtpValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Undefined => f.write_str("-"),
+            Self::Int8(v) => write!(f, "{v}"),
+            Self::Uint8(v) => write!(f, "{v}"),
+            Self::Int16(v) => write!(f, "{v}"),
+            Self::Uint16(v) => write!(f, "{v}"),
+            Self::Int32(v) => write!(f, "{v}"),
+            Self::Uint32(v) => write!(f, "{v}"),
+            Self::Int64(v) => write!(f, "{v}"),
+            Self::Uint64(v) => write!(f, "{v}"),
+            Self::Int128(v) => write!(f, "{v}"),
+            Self::Uint128(v) => write!(f, "{v}"),
+            Self::ArrayInt8(v) => fmt_array(f, v),
+            Self::ArrayUint8(v) => fmt_array(f, v),
+            Self::ArrayInt16(v) => fmt_array(f, v),
+            Self::ArrayUint16(v) => fmt_array(f, v),
+            Self::ArrayInt32(v) => fmt_array(f, v),
+            Self::ArrayUint32(v) => fmt_array(f, v),
+            Self::ArrayInt64(v) => fmt_array(f, v),
+            Self::ArrayUint64(v) => fmt_array(f, v),
+            Self::ArrayInt128(v) => fmt_array(f, v),
+            Self::ArrayUint128(v) => fmt_array(f, v),
+            Self::Str(v) => write!(f, "{v:?}"),
+        }
+    }
+}
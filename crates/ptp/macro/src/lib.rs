@@ -21,13 +21,10 @@ pub fn derive_ptp_serialize(input: TokenStream) -> TokenStream {
 
                 quote! {
                     impl ptp_cursor::PtpSerialize for #name {
-                        fn try_into_ptp(&self) -> std::io::Result<Vec<u8>> {
-                            let mut buf = Vec::new();
-                            self.try_write_ptp(&mut buf)?;
-                            Ok(buf)
-                        }
-
-                        fn try_write_ptp(&self, buf: &mut Vec<u8>) -> std::io::Result<()> {
+                        fn try_write_ptp<W: ptp_cursor::Write + ?Sized>(
+                            &self,
+                            buf: &mut W,
+                        ) -> std::io::Result<()> {
                             #(#write_fields)*
                             Ok(())
                         }
@@ -44,13 +41,10 @@ pub fn derive_ptp_serialize(input: TokenStream) -> TokenStream {
 
                 quote! {
                     impl ptp_cursor::PtpSerialize for #name {
-                        fn try_into_ptp(&self) -> std::io::Result<Vec<u8>> {
-                            let mut buf = Vec::new();
-                            self.try_write_ptp(&mut buf)?;
-                            Ok(buf)
-                        }
-
-                        fn try_write_ptp(&self, buf: &mut Vec<u8>) -> std::io::Result<()> {
+                        fn try_write_ptp<W: ptp_cursor::Write + ?Sized>(
+                            &self,
+                            buf: &mut W,
+                        ) -> std::io::Result<()> {
                             #(#write_fields)*
                             Ok(())
                         }
@@ -60,11 +54,10 @@ pub fn derive_ptp_serialize(input: TokenStream) -> TokenStream {
             Fields::Unit => {
                 quote! {
                     impl ptp_cursor::PtpSerialize for #name {
-                        fn try_into_ptp(&self) -> std::io::Result<Vec<u8>> {
-                            Ok(Vec::new())
-                        }
-
-                        fn try_write_ptp(&self, _buf: &mut Vec<u8>) -> std::io::Result<()> {
+                        fn try_write_ptp<W: ptp_cursor::Write + ?Sized>(
+                            &self,
+                            _buf: &mut W,
+                        ) -> std::io::Result<()> {
                             Ok(())
                         }
                     }
@@ -84,13 +77,10 @@ pub fn derive_ptp_serialize(input: TokenStream) -> TokenStream {
                 where
                     #name: Clone + Copy + TryFrom<#repr_ty> + Into<#repr_ty>
                 {
-                    fn try_into_ptp(&self) -> std::io::Result<Vec<u8>> {
-                        let mut buf = Vec::new();
-                        self.try_write_ptp(&mut buf)?;
-                        Ok(buf)
-                    }
-
-                    fn try_write_ptp(&self, buf: &mut Vec<u8>) -> std::io::Result<()> {
+                    fn try_write_ptp<W: ptp_cursor::Write + ?Sized>(
+                        &self,
+                        buf: &mut W,
+                    ) -> std::io::Result<()> {
                         let discriminant: #repr_ty = (*self).into();
                         discriminant.try_write_ptp(buf)?;
                         Ok(())
@@ -117,10 +107,12 @@ pub fn derive_ptp_deserialize(input: TokenStream) -> TokenStream {
                 let fields = &named.named;
 
                 let read_fields = fields.iter().map(|f| {
-                    let name = &f.ident;
+                    let field = &f.ident;
                     let ty = &f.ty;
                     quote! {
-                        #name: <#ty>::try_read_ptp(cur)?
+                        #field: <#ty>::try_read_ptp(cur).map_err(|e| {
+                            ptp_cursor::with_context(e, concat!(stringify!(#name), ".", stringify!(#field)))
+                        })?
                     }
                 });
 
@@ -144,9 +136,14 @@ pub fn derive_ptp_deserialize(input: TokenStream) -> TokenStream {
             Fields::Unnamed(unnamed) => {
                 let fields = &unnamed.unnamed;
 
-                let read_fields = fields.iter().map(|f| {
+                let read_fields = fields.iter().enumerate().map(|(i, f)| {
                     let ty = &f.ty;
-                    quote! { <#ty>::try_read_ptp(cur)? }
+                    let idx = i.to_string();
+                    quote! {
+                        <#ty>::try_read_ptp(cur).map_err(|e| {
+                            ptp_cursor::with_context(e, concat!(stringify!(#name), ".", #idx))
+                        })?
+                    }
                 });
 
                 quote! {
@@ -225,3 +222,67 @@ pub fn derive_ptp_deserialize(input: TokenStream) -> TokenStream {
 
     expanded.into()
 }
+
+fn is_unknown_field(field: &syn::Field) -> bool {
+    field.attrs.iter().any(|attr| {
+        attr.path().is_ident("ptp")
+            && attr
+                .parse_args::<syn::Ident>()
+                .is_ok_and(|ident| ident == "unknown")
+    })
+}
+
+#[proc_macro_derive(PtpLayout, attributes(ptp))]
+pub fn derive_ptp_layout(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+
+    let read_fields = match &input.data {
+        Data::Struct(s) => match &s.fields {
+            Fields::Named(named) => named
+                .named
+                .iter()
+                .map(|f| {
+                    let name = &f.ident;
+                    let ty = &f.ty;
+                    if is_unknown_field(f) {
+                        quote! { reader.unknown::<#ty>(stringify!(#name))?; }
+                    } else {
+                        quote! { reader.field::<#ty>(stringify!(#name))?; }
+                    }
+                })
+                .collect::<Vec<_>>(),
+            Fields::Unnamed(unnamed) => unnamed
+                .unnamed
+                .iter()
+                .enumerate()
+                .map(|(i, f)| {
+                    let ty = &f.ty;
+                    let idx = i.to_string();
+                    if is_unknown_field(f) {
+                        quote! { reader.unknown::<#ty>(#idx)?; }
+                    } else {
+                        quote! { reader.field::<#ty>(#idx)?; }
+                    }
+                })
+                .collect::<Vec<_>>(),
+            Fields::Unit => Vec::new(),
+        },
+        Data::Enum(_) => vec![quote! { reader.field::<Self>(stringify!(#name))?; }],
+        Data::Union(_) => {
+            unimplemented!("PtpLayout cannot be automatically derived for unions")
+        }
+    };
+
+    let expanded = quote! {
+        impl ptp_cursor::PtpLayout for #name {
+            #[allow(unused_variables)]
+            fn read_ptp_layout(reader: &mut ptp_cursor::LayoutReader<'_>) -> std::io::Result<()> {
+                #(#read_fields)*
+                Ok(())
+            }
+        }
+    };
+
+    expanded.into()
+}
@@ -22,10 +22,13 @@ pub enum BackupCmd {
 #[allow(clippy::needless_pass_by_value)]
 fn handle_export(options: GlobalOptions, output: Output) -> anyhow::Result<()> {
     let GlobalOptions {
-        device, emulate, ..
+        device,
+        emulate,
+        dry_run,
+        ..
     } = options;
 
-    let mut camera = usb::get_camera(device, emulate)?;
+    let mut camera = usb::get_camera(device, emulate, dry_run)?;
 
     let mut writer = output.get_writer()?;
     let backup = camera.export_backup()?;
@@ -37,10 +40,13 @@ fn handle_export(options: GlobalOptions, output: Output) -> anyhow::Result<()> {
 #[allow(clippy::needless_pass_by_value)]
 fn handle_import(options: GlobalOptions, input: Input) -> anyhow::Result<()> {
     let GlobalOptions {
-        device, emulate, ..
+        device,
+        emulate,
+        dry_run,
+        ..
     } = options;
 
-    let mut camera = usb::get_camera(device, emulate)?;
+    let mut camera = usb::get_camera(device, emulate, dry_run)?;
 
     let mut reader = input.get_reader()?;
     let mut backup = Vec::new();
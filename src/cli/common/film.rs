@@ -1,12 +1,18 @@
+use std::{fmt, str::FromStr};
+
 use clap::Args;
 
-use fujicli::ptp::fuji;
+use fujicli::{
+    features::simulation::{Recipe, SupportedValues, values},
+    input::Choices,
+    ptp::fuji,
+};
 
 #[derive(Args, Debug)]
 pub struct FilmSimulationOptions {
     /// Fujifilm Film Simulation
     #[clap(long)]
-    pub simulation: Option<fuji::FilmSimulation>,
+    pub simulation: Option<String>,
 
     /// Monochromatic Color Temperature (only applicable to B&W film simulations)
     #[clap(long)]
@@ -16,13 +22,13 @@ pub struct FilmSimulationOptions {
     #[clap(long)]
     pub monochromatic_color_tint: Option<fuji::MonochromaticColorShift>,
 
-    /// The output image resolution
+    /// The output image size as aspect ratio and L/M/S (e.g. 3:2-L)
     #[clap(long)]
-    pub size: Option<fuji::ImageSize>,
+    pub size: Option<String>,
 
     /// The output image quality (JPEG compression level)
     #[clap(long)]
-    pub quality: Option<fuji::ImageQuality>,
+    pub quality: Option<String>,
 
     /// Highlight Tone
     #[clap(long, allow_hyphen_values(true))]
@@ -46,7 +52,7 @@ pub struct FilmSimulationOptions {
 
     /// White Balance
     #[clap(long)]
-    pub white_balance: Option<fuji::WhiteBalance>,
+    pub white_balance: Option<String>,
 
     /// White Balance Shift Red
     #[clap(long, allow_hyphen_values(true))]
@@ -62,11 +68,11 @@ pub struct FilmSimulationOptions {
 
     /// Dynamic Range
     #[clap(long)]
-    pub dynamic_range: Option<fuji::DynamicRange>,
+    pub dynamic_range: Option<String>,
 
     /// Dynamic Range Priority
     #[clap(long)]
-    pub dynamic_range_priority: Option<fuji::DynamicRangePriority>,
+    pub dynamic_range_priority: Option<String>,
 
     /// High ISO Noise Reduction
     #[clap(long, allow_hyphen_values(true))]
@@ -74,7 +80,7 @@ pub struct FilmSimulationOptions {
 
     /// Grain Effect
     #[clap(long)]
-    pub grain: Option<fuji::GrainEffect>,
+    pub grain: Option<String>,
 
     /// Color Chrome Effect
     #[clap(long)]
@@ -96,3 +102,79 @@ pub struct FilmSimulationOptions {
     #[clap(long)]
     pub color_space: Option<fuji::ColorSpace>,
 }
+
+// Values that differ between models are parsed against the target model, so that only what it
+// supports is accepted and suggested.
+impl FilmSimulationOptions {
+    pub fn to_recipe(&self, model: &str, values: &SupportedValues) -> anyhow::Result<Recipe> {
+        Ok(Recipe {
+            name: None,
+            size: self
+                .size
+                .as_deref()
+                .map(|size| values.parse_image_size(model, size))
+                .transpose()?,
+            quality: parse(
+                model,
+                self.quality.as_deref(),
+                "Quality",
+                values.image_qualities,
+            )?,
+            simulation: parse(
+                model,
+                self.simulation.as_deref(),
+                "Simulation",
+                values.film_simulations,
+            )?,
+            monochromatic_color_temperature: self.monochromatic_color_temperature,
+            monochromatic_color_tint: self.monochromatic_color_tint,
+            highlight: self.highlight,
+            shadow: self.shadow,
+            color: self.color,
+            sharpness: self.sharpness,
+            clarity: self.clarity,
+            noise_reduction: self.noise_reduction,
+            grain: parse(model, self.grain.as_deref(), "Grain", values.grain_effects)?,
+            color_chrome_effect: self.color_chrome_effect,
+            color_chrome_fx_blue: self.color_chrome_fx_blue,
+            smooth_skin_effect: self.smooth_skin_effect,
+            white_balance: parse(
+                model,
+                self.white_balance.as_deref(),
+                "White Balance",
+                values.white_balances,
+            )?,
+            white_balance_shift_red: self.white_balance_shift_red,
+            white_balance_shift_blue: self.white_balance_shift_blue,
+            white_balance_temperature: self.white_balance_temperature,
+            dynamic_range: parse(
+                model,
+                self.dynamic_range.as_deref(),
+                "Dynamic Range",
+                values.dynamic_ranges,
+            )?,
+            dynamic_range_priority: parse(
+                model,
+                self.dynamic_range_priority.as_deref(),
+                "Dynamic Range Priority",
+                values.dynamic_range_priorities,
+            )?,
+            lens_modulation_optimizer: self.lens_modulation_optimizer,
+            color_space: self.color_space,
+        })
+    }
+}
+
+fn parse<T>(
+    model: &str,
+    input: Option<&str>,
+    label: &str,
+    supported: Option<&[T]>,
+) -> anyhow::Result<Option<T>>
+where
+    T: FromStr<Err = anyhow::Error> + PartialEq + fmt::Display + Choices,
+{
+    input
+        .map(|input| values::parse(model, label, supported, input))
+        .transpose()
+}
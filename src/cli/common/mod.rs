@@ -1,3 +1,5 @@
 pub mod file;
 pub mod film;
+pub mod slot;
+pub mod source;
 pub mod usb;
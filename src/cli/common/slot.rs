@@ -0,0 +1,41 @@
+use std::{fmt, str::FromStr};
+
+use fujicli::{Camera, ptp::fuji};
+
+// A slot given either by number or by its custom setting name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SlotRef {
+    Slot(fuji::CustomSetting),
+    Name(String),
+}
+
+impl FromStr for SlotRef {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        if let Ok(slot) = s.parse() {
+            return Ok(Self::Slot(slot));
+        }
+
+        let name: fuji::CustomSettingName = s.parse()?;
+        Ok(Self::Name(name.to_string()))
+    }
+}
+
+impl fmt::Display for SlotRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Slot(slot) => write!(f, "{slot}"),
+            Self::Name(name) => write!(f, "'{name}'"),
+        }
+    }
+}
+
+impl SlotRef {
+    pub fn resolve(&self, camera: &mut Camera) -> anyhow::Result<fuji::CustomSetting> {
+        match self {
+            Self::Slot(slot) => Ok(*slot),
+            Self::Name(name) => camera.find_slot(name),
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use std::{fmt, fs, path::PathBuf, str::FromStr};
+
+use anyhow::bail;
+use fujicli::{
+    Camera,
+    features::{
+        image::extract_simulation,
+        simulation::{Recipe, SimulationFormat},
+    },
+};
+
+use super::slot::SlotRef;
+
+const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "raf", "heif", "hif", "tif", "tiff"];
+
+#[derive(Debug, Clone)]
+pub enum SimulationSource {
+    Slot(SlotRef),
+    File(PathBuf),
+    Image(PathBuf),
+}
+
+impl FromStr for SimulationSource {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let path = PathBuf::from(s);
+        let is_image = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.to_lowercase().as_str()));
+
+        if path.is_file() {
+            return Ok(if is_image {
+                Self::Image(path)
+            } else {
+                Self::File(path)
+            });
+        }
+
+        // Anything that looks like a file is not taken for a slot name.
+        let is_file_like = is_image
+            || s.contains(std::path::MAIN_SEPARATOR)
+            || SimulationFormat::from_path(&path).is_some();
+
+        match s.parse() {
+            Ok(slot) if !is_file_like => Ok(Self::Slot(slot)),
+            _ => bail!("'{s}' is neither a simulation slot nor an existing file"),
+        }
+    }
+}
+
+impl fmt::Display for SimulationSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Slot(slot) => write!(f, "{slot}"),
+            Self::File(path) | Self::Image(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+impl SimulationSource {
+    pub const fn is_slot(&self) -> bool {
+        matches!(self, Self::Slot(_))
+    }
+
+    pub fn load(&self, camera: Option<&mut Camera>) -> anyhow::Result<Recipe> {
+        match self {
+            Self::Slot(slot) => {
+                let Some(camera) = camera else {
+                    bail!("A camera is required to read slot {slot}");
+                };
+                let slot = slot.resolve(camera)?;
+                Ok(camera.get_simulation(slot)?.to_recipe())
+            }
+            Self::File(path) => {
+                let format = SimulationFormat::detect(None, Some(path));
+                Recipe::from_bytes(&fs::read(path)?, format, path.parent())
+            }
+            Self::Image(path) => Ok(extract_simulation(path)?.to_recipe()),
+        }
+    }
+}
@@ -99,7 +99,17 @@ pub fn get_all_cameras() -> anyhow::Result<Vec<Camera>> {
     Ok(cameras)
 }
 
-pub fn get_camera(device: Option<Location>, emulate: Option<Identity>) -> anyhow::Result<Camera> {
+pub fn get_camera(
+    device: Option<Location>,
+    emulate: Option<Identity>,
+    dry_run: bool,
+) -> anyhow::Result<Camera> {
+    let mut camera = open_camera(device, emulate)?;
+    camera.set_dry_run(dry_run);
+    Ok(camera)
+}
+
+fn open_camera(device: Option<Location>, emulate: Option<Identity>) -> anyhow::Result<Camera> {
     if let Some(location) = device {
         let device = get_usb_device_by_location(location)?;
 
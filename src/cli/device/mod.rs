@@ -55,10 +55,11 @@ fn handle_info(options: GlobalOptions) -> anyhow::Result<()> {
         json,
         device,
         emulate,
+        dry_run,
         ..
     } = options;
 
-    let mut camera = usb::get_camera(device, emulate)?;
+    let mut camera = usb::get_camera(device, emulate, dry_run)?;
 
     let repr = camera.get_info()?;
 
@@ -3,9 +3,9 @@ use clap::Subcommand;
 use fujicli::{
     Camera,
     features::backup,
-    ptp::{CommandCode, DevicePropCode, fuji},
+    ptp::{CommandCode, DeviceInfo, DevicePropCode, ObjectInfo, fuji, log_layout},
 };
-use log::{debug, error};
+use log::{Level, debug, error};
 use ptp_cursor::PtpSerialize;
 use strum::IntoEnumIterator;
 
@@ -31,6 +31,10 @@ pub enum ReverseCmd {
     /// Get information about supported simulation management commands
     #[command(alias = "s")]
     Simulation,
+
+    /// Dump descriptors of all supported device properties
+    #[command(alias = "p")]
+    Props,
 }
 
 macro_rules! try_call {
@@ -53,11 +57,13 @@ fn handle_backup_export(options: GlobalOptions, output: Output) -> anyhow::Resul
     let mut camera = Camera::open_unknown(&usb)?;
 
     let mut writer = output.get_writer()?;
-    try_call!(camera.ptp.send(
+    let object_info = try_call!(camera.ptp.send(
         CommandCode::GetObjectInfo,
         &backup::EXPORT_OBJECT_INFO_HANDLE,
         None
     ))?;
+    log_layout::<ObjectInfo>(Level::Debug, "Backup object info", &object_info);
+
     let backup = try_call!(
         camera
             .ptp
@@ -80,12 +86,13 @@ fn handle_backup_import(options: GlobalOptions, input: Input) -> anyhow::Result<
     let mut backup = Vec::new();
     reader.read_to_end(&mut backup)?;
 
-    let backup_info = fuji::BackupObjectInfo::new(backup.len())?;
+    let backup_info = fuji::BackupObjectInfo::new(backup.len())?.try_into_ptp()?;
+    log_layout::<ObjectInfo>(Level::Debug, "Backup object info", &backup_info);
 
     try_call!(camera.ptp.send(
         CommandCode::SendObjectInfo,
         &backup::IMPORT_OBJECT_INFO_HANDLE,
-        Some(&backup_info.try_into_ptp()?),
+        Some(&backup_info),
     ))?;
     try_call!(camera.ptp.send(
         CommandCode::SendObject,
@@ -104,10 +111,40 @@ fn handle_info(options: GlobalOptions) -> anyhow::Result<()> {
     let usb = usb::get_usb_device_by_location(location)?;
     let mut camera = Camera::open_unknown(&usb)?;
 
-    let _ = try_call!(camera.ptp.get_info());
+    if let Ok(info) = try_call!(camera.ptp.send(CommandCode::GetDeviceInfo, &[], None)) {
+        log_layout::<DeviceInfo>(Level::Debug, "Device info", &info);
+    }
     let _ = try_call!(camera.ptp.get_prop_raw(DevicePropCode::FujiUsbMode));
     let _ = try_call!(camera.ptp.get_prop_raw(DevicePropCode::FujiBatteryInfo2));
 
+    if let Ok(storage_ids) = try_call!(camera.ptp.get_storage_ids()) {
+        for storage_id in storage_ids {
+            let _ = try_call!(camera.ptp.get_storage_info(storage_id));
+        }
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn handle_props(options: GlobalOptions) -> anyhow::Result<()> {
+    let GlobalOptions { device, .. } = options;
+
+    let location = device.ok_or_else(|| anyhow!("Device must be specified for prop dump"))?;
+    let usb = usb::get_usb_device_by_location(location)?;
+    let mut camera = Camera::open_unknown(&usb)?;
+
+    let info = try_call!(camera.ptp.get_info())?;
+    for code in info.device_properties_supported {
+        let name = DevicePropCode::try_from(u32::from(code))
+            .map_or_else(|_| String::from("Unknown"), |c| format!("{c:?}"));
+
+        match try_call!(camera.ptp.get_prop_desc(u32::from(code))) {
+            Ok(desc) => println!("{desc} [{name}]"),
+            Err(_) => println!("0x{code:04x} <unavailable> [{name}]"),
+        }
+    }
+
     Ok(())
 }
 
@@ -264,5 +301,6 @@ pub fn handle(cmd: ReverseCmd, options: GlobalOptions) -> anyhow::Result<()> {
         ReverseCmd::Backup(BackupCmd::Import { input }) => handle_backup_import(options, input),
         ReverseCmd::Info => handle_info(options),
         ReverseCmd::Simulation => handle_simulation(options),
+        ReverseCmd::Props => handle_props(options),
     }
 }
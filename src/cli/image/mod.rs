@@ -1,29 +1,67 @@
-use fujicli::{features::image::extract_simulation, ptp::fuji};
+use std::path::Path;
+
+use fujicli::{
+    features::{
+        image::extract_simulation,
+        simulation::{RecipeLibrary, Simulation, SimulationFormat},
+    },
+    ptp::fuji,
+};
 
 use super::common::{
     file::{Input, Output},
     film::FilmSimulationOptions,
+    slot::SlotRef,
 };
 use crate::cli::{GlobalOptions, common::usb};
 use clap::{Args, Subcommand};
 
 #[derive(Subcommand, Debug)]
+#[allow(clippy::large_enum_variant)]
 pub enum ImageCmd {
     /// Render image
     #[command(alias = "r")]
     Render {
-        /// Simulation slot number
-        #[arg(long, conflicts_with = "simulation_file", conflicts_with = "like")]
-        slot: Option<fuji::CustomSetting>,
+        /// Simulation slot number or name
+        #[arg(
+            long,
+            conflicts_with = "simulation_file",
+            conflicts_with = "like",
+            conflicts_with = "recipe"
+        )]
+        slot: Option<SlotRef>,
 
         /// Path to exported simulation file
-        #[arg(long, conflicts_with = "slot", conflicts_with = "like")]
+        #[arg(
+            long,
+            conflicts_with = "slot",
+            conflicts_with = "like",
+            conflicts_with = "recipe"
+        )]
         simulation_file: Option<Input>,
 
+        /// Format of the simulation file (json, toml, yaml or text, detected from the extension by default)
+        #[arg(long, requires = "simulation_file")]
+        simulation_format: Option<SimulationFormat>,
+
         /// Path to image to mimic simulation settings from (use '-' to read from stdin)
-        #[arg(long, conflicts_with = "slot", conflicts_with = "simulation_file")]
+        #[arg(
+            long,
+            conflicts_with = "slot",
+            conflicts_with = "simulation_file",
+            conflicts_with = "recipe"
+        )]
         like: Option<Input>,
 
+        /// Name of a recipe in the library
+        #[arg(
+            long,
+            conflicts_with = "slot",
+            conflicts_with = "simulation_file",
+            conflicts_with = "like"
+        )]
+        recipe: Option<String>,
+
         #[command(flatten)]
         render_options: RenderOptions,
 
@@ -67,19 +105,8 @@ pub struct RenderOptions {
     teleconverter: Option<fuji::Teleconverter>,
 }
 
-macro_rules! update_conversion_profile {
-    ($sim_var:ident, [
-        $($local_field:ident => $setter:ident,)*
-    ]) => {
-        $(
-            if let Some(value) = $local_field {
-                $sim_var.$setter(value)?;
-            }
-        )*
-    };
-}
-
 #[allow(clippy::too_many_arguments)]
+#[allow(clippy::too_many_lines)]
 #[allow(clippy::needless_pass_by_value)]
 fn handle_render(
     options: GlobalOptions,
@@ -87,15 +114,20 @@ fn handle_render(
     film_options: &FilmSimulationOptions,
     input: Input,
     output: Output,
-    slot: Option<fuji::CustomSetting>,
+    slot: Option<SlotRef>,
     simulation_file: Option<Input>,
+    simulation_format: Option<SimulationFormat>,
     like: Option<Input>,
+    recipe: Option<&str>,
 ) -> anyhow::Result<()> {
     let GlobalOptions {
-        device, emulate, ..
+        device,
+        emulate,
+        dry_run,
+        ..
     } = options;
 
-    let mut camera = usb::get_camera(device, emulate)?;
+    let mut camera = usb::get_camera(device, emulate, dry_run)?;
 
     let RenderOptions {
         draft,
@@ -104,95 +136,59 @@ fn handle_render(
         teleconverter,
     } = render_options;
 
-    let FilmSimulationOptions {
-        simulation: film_simulation,
-        monochromatic_color_temperature,
-        monochromatic_color_tint,
-        size,
-        quality,
-        highlight,
-        shadow,
-        color,
-        sharpness,
-        clarity,
-        white_balance,
-        white_balance_shift_red,
-        white_balance_shift_blue,
-        white_balance_temperature,
-        dynamic_range,
-        dynamic_range_priority,
-        noise_reduction,
-        grain,
-        color_chrome_effect,
-        color_chrome_fx_blue,
-        smooth_skin_effect,
-        lens_modulation_optimizer,
-        color_space,
-    } = film_options;
+    let film_options = film_options.to_recipe(camera.name(), camera.supported_values()?)?;
 
     let mut reader = input.get_reader()?;
     let mut image = Vec::new();
     reader.read_to_end(&mut image)?;
 
     let simulation = if let Some(slot) = slot {
+        let slot = slot.resolve(&mut camera)?;
         Some(camera.get_simulation(slot)?)
     } else if let Some(simulation_file) = simulation_file {
+        let format = SimulationFormat::detect(simulation_format, simulation_file.path());
         let mut reader = simulation_file.get_reader()?;
         let mut simulation = Vec::new();
         reader.read_to_end(&mut simulation)?;
-        Some(camera.deserialize_simulation(&simulation)?)
+        let base_dir = simulation_file.path().and_then(Path::parent);
+        Some(camera.deserialize_simulation(&simulation, format, base_dir)?)
     } else if let Some(like) = like {
         let like = like.into_path()?;
         Some(extract_simulation(&like)?)
+    } else if let Some(recipe) = recipe {
+        let recipe = RecipeLibrary::open_default()?.get(recipe)?;
+        Some(Box::new(recipe.recipe) as Box<dyn Simulation>)
     } else {
         None
     };
 
     let rendered = camera.render(
         &image,
-        #[allow(clippy::cognitive_complexity)]
         &mut |conversion_profile| {
             if let Some(simulation) = simulation.as_deref() {
                 conversion_profile.set_from_simulation(simulation)?;
             }
 
-            update_conversion_profile! {
-                conversion_profile,
-                [
-                    file_type => set_file_type,
-                    film_simulation => set_simulation,
-                    monochromatic_color_temperature => set_monochromatic_color_temperature,
-                    monochromatic_color_tint => set_monochromatic_color_tint,
-                    size => set_size,
-                    quality => set_quality,
-                    exposure_offset => set_exposure_offset,
-                    highlight => set_highlight,
-                    shadow => set_shadow,
-                    color => set_color,
-                    sharpness => set_sharpness,
-                    clarity => set_clarity,
-                    white_balance => set_white_balance,
-                    white_balance_shift_red => set_white_balance_shift_red,
-                    white_balance_shift_blue => set_white_balance_shift_blue,
-                    white_balance_temperature => set_white_balance_temperature,
-                    dynamic_range => set_dynamic_range,
-                    dynamic_range_priority => set_dynamic_range_priority,
-                    noise_reduction => set_noise_reduction,
-                    grain => set_grain,
-                    color_chrome_effect => set_color_chrome_effect,
-                    color_chrome_fx_blue => set_color_chrome_fx_blue,
-                    smooth_skin_effect => set_smooth_skin_effect,
-                    lens_modulation_optimizer => set_lens_modulation_optimizer,
-                    color_space => set_color_space,
-                    teleconverter => set_teleconverter,
-                ]
-            };
+            if let Some(file_type) = file_type {
+                conversion_profile.set_file_type(file_type)?;
+            }
+            if let Some(exposure_offset) = exposure_offset {
+                conversion_profile.set_exposure_offset(exposure_offset)?;
+            }
+            if let Some(teleconverter) = teleconverter {
+                conversion_profile.set_teleconverter(teleconverter)?;
+            }
+            film_options.set_on_profile(conversion_profile)?;
 
             Ok(())
         },
         *draft,
     )?;
 
+    if camera.is_dry_run() {
+        return Ok(());
+    }
+
     let mut writer = output.get_writer()?;
     writer.write_all(&rendered)?;
 
@@ -218,7 +214,9 @@ pub fn handle(cmd: ImageCmd, options: GlobalOptions) -> anyhow::Result<()> {
         ImageCmd::Render {
             slot,
             simulation_file,
+            simulation_format,
             like,
+            recipe,
             input,
             output,
             render_options,
@@ -231,7 +229,9 @@ pub fn handle(cmd: ImageCmd, options: GlobalOptions) -> anyhow::Result<()> {
             output,
             slot,
             simulation_file,
+            simulation_format,
             like,
+            recipe.as_deref(),
         ),
         ImageCmd::Extract { input, output } => handle_extract(input, output),
     }
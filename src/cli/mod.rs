@@ -2,6 +2,7 @@ pub mod backup;
 pub mod common;
 pub mod device;
 pub mod image;
+pub mod recipe;
 pub mod simulation;
 
 use clap::{ArgAction, Args, Parser, Subcommand};
@@ -9,6 +10,7 @@ use clap::{ArgAction, Args, Parser, Subcommand};
 use backup::BackupCmd;
 use device::DeviceCmd;
 use image::ImageCmd;
+use recipe::RecipeCmd;
 use simulation::SimulationCmd;
 
 use crate::cli::common::usb::{Identity, Location};
@@ -38,6 +40,10 @@ pub struct GlobalOptions {
     #[arg(long, short = 'd', global = true)]
     pub device: Option<Location>,
 
+    /// Perform reads and validation, but only print the writes that would be made
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+
     #[allow(clippy::doc_markdown)]
     /// Treat device as a different model using <VENDOR_ID>:<PRODUCT_ID>
     #[arg(long, global = true)]
@@ -54,6 +60,10 @@ pub enum Commands {
     #[command(alias = "s", subcommand)]
     Simulation(SimulationCmd),
 
+    /// Manage the local recipe library
+    #[command(alias = "r", subcommand)]
+    Recipe(RecipeCmd),
+
     /// Manage backups
     #[command(alias = "b", subcommand)]
     Backup(BackupCmd),
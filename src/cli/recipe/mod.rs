@@ -0,0 +1,198 @@
+use fujicli::{
+    features::simulation::{LibraryRecipe, RecipeLibrary},
+    ptp::fuji,
+};
+
+use super::common::source::SimulationSource;
+use crate::cli::{GlobalOptions, common::usb};
+use clap::Subcommand;
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum RecipeCmd {
+    /// Add a recipe to the library
+    #[command(alias = "a")]
+    Add {
+        /// Recipe name
+        name: String,
+
+        /// Simulation slot, exported file or image to take the recipe from
+        source: SimulationSource,
+
+        /// Tag the recipe (can be repeated)
+        #[clap(long = "tag")]
+        tags: Vec<String>,
+
+        /// Free-form notes about the recipe
+        #[clap(long)]
+        notes: Option<String>,
+
+        /// Replace an existing recipe with the same name
+        #[clap(long)]
+        force: bool,
+    },
+
+    /// List recipes in the library
+    #[command(alias = "l")]
+    List {
+        /// Only list recipes with this tag
+        #[clap(long)]
+        tag: Option<String>,
+    },
+
+    /// Show a recipe
+    #[command(alias = "s")]
+    Show {
+        /// Recipe name
+        name: String,
+    },
+
+    /// Remove a recipe from the library
+    #[command(alias = "rm")]
+    Remove {
+        /// Recipe name
+        name: String,
+    },
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn handle_add(
+    options: GlobalOptions,
+    name: String,
+    source: &SimulationSource,
+    tags: Vec<String>,
+    notes: Option<String>,
+    force: bool,
+) -> anyhow::Result<()> {
+    let GlobalOptions {
+        device,
+        emulate,
+        dry_run,
+        ..
+    } = options;
+
+    let library = RecipeLibrary::open_default()?;
+
+    let mut camera = if source.is_slot() {
+        Some(usb::get_camera(device, emulate, dry_run)?)
+    } else {
+        None
+    };
+
+    // The library name only names the recipe when it does not carry a name of its own.
+    let mut recipe = source.load(camera.as_mut())?;
+    if recipe.name.is_none()
+        && let Ok(slot_name) = name.parse::<fuji::CustomSettingName>()
+    {
+        recipe.name = Some(slot_name);
+    }
+
+    let recipe = LibraryRecipe {
+        name,
+        tags,
+        notes,
+        recipe,
+    };
+
+    if dry_run {
+        println!(
+            "Would add '{}' to {}",
+            recipe.name,
+            library.root().display()
+        );
+        return Ok(());
+    }
+
+    library.add(&recipe, force)?;
+
+    Ok(())
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn handle_list(options: GlobalOptions, tag: Option<&str>) -> anyhow::Result<()> {
+    let GlobalOptions { json, .. } = options;
+
+    let library = RecipeLibrary::open_default()?;
+    let recipes: Vec<LibraryRecipe> = library
+        .list()?
+        .into_iter()
+        .filter(|recipe| {
+            tag.is_none_or(|tag| recipe.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+        })
+        .collect();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&recipes)?);
+        return Ok(());
+    }
+
+    if recipes.is_empty() {
+        println!("No recipes found in {}", library.root().display());
+        return Ok(());
+    }
+
+    for recipe in recipes {
+        if recipe.tags.is_empty() {
+            println!("- {}", recipe.name);
+        } else {
+            println!("- {} [{}]", recipe.name, recipe.tags.join(", "));
+        }
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn handle_show(options: GlobalOptions, name: &str) -> anyhow::Result<()> {
+    let GlobalOptions { json, .. } = options;
+
+    let recipe = RecipeLibrary::open_default()?.get(name)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&recipe)?);
+        return Ok(());
+    }
+
+    println!("Recipe: {}", recipe.name);
+    if !recipe.tags.is_empty() {
+        println!("Tags: {}", recipe.tags.join(", "));
+    }
+    if let Some(notes) = &recipe.notes {
+        println!("Notes: {notes}");
+    }
+    println!();
+    print!("{}", recipe.recipe);
+
+    Ok(())
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn handle_remove(options: GlobalOptions, name: &str) -> anyhow::Result<()> {
+    let GlobalOptions { dry_run, .. } = options;
+
+    let library = RecipeLibrary::open_default()?;
+
+    if dry_run {
+        println!("Would remove '{}'", library.get(name)?.name);
+        return Ok(());
+    }
+
+    let recipe = library.remove(name)?;
+    println!("Removed '{}'", recipe.name);
+
+    Ok(())
+}
+
+pub fn handle(cmd: RecipeCmd, options: GlobalOptions) -> anyhow::Result<()> {
+    match cmd {
+        RecipeCmd::Add {
+            name,
+            source,
+            tags,
+            notes,
+            force,
+        } => handle_add(options, name, &source, tags, notes, force),
+        RecipeCmd::List { tag } => handle_list(options, tag.as_deref()),
+        RecipeCmd::Show { name } => handle_show(options, &name),
+        RecipeCmd::Remove { name } => handle_remove(options, &name),
+    }
+}
@@ -0,0 +1,422 @@
+use std::io::{self, IsTerminal, Write};
+
+use anyhow::bail;
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute, queue,
+    style::{Attribute, Color, Print, ResetColor, SetAttribute, SetForegroundColor},
+    terminal::{self, ClearType},
+};
+use fujicli::{
+    features::simulation::{Recipe, Simulation, SupportedValues, rules, rules::Severity},
+    ptp::fuji,
+};
+use log::info;
+
+use crate::cli::{
+    GlobalOptions,
+    common::{slot::SlotRef, usb},
+};
+
+// Page Up and Page Down move numeric fields this many steps at a time.
+const PAGE_STEPS: usize = 10;
+
+struct Field {
+    key: String,
+    label: &'static str,
+    choices: Vec<String>,
+    original: String,
+}
+
+struct Editor {
+    model: &'static str,
+    values: &'static SupportedValues,
+    slot: fuji::CustomSetting,
+    simulation: Box<dyn Simulation>,
+    original: Recipe,
+    fields: Vec<Field>,
+    selected: usize,
+    offset: usize,
+    // Text typed into a free-form field, while it is being edited.
+    input: Option<String>,
+    message: Option<String>,
+}
+
+enum Outcome {
+    Save,
+    Quit,
+}
+
+impl Editor {
+    fn new(
+        model: &'static str,
+        values: &'static SupportedValues,
+        slot: fuji::CustomSetting,
+        simulation: Box<dyn Simulation>,
+    ) -> anyhow::Result<Self> {
+        // Fields the model does not have cannot be read, so they are left out.
+        let mut fields = Vec::new();
+        for (key, label) in <dyn Simulation>::field_labels() {
+            if let Ok(original) = simulation.get_field(&key) {
+                fields.push(Field {
+                    choices: <dyn Simulation>::field_choices(&key, values)?,
+                    key,
+                    label,
+                    original,
+                });
+            }
+        }
+        if fields.is_empty() {
+            bail!("No field of slot {slot} could be read, there is nothing to edit");
+        }
+
+        Ok(Self {
+            model,
+            values,
+            slot,
+            original: simulation.to_recipe(),
+            simulation,
+            fields,
+            selected: 0,
+            offset: 0,
+            input: None,
+            message: None,
+        })
+    }
+
+    fn value(&self, field: &Field) -> String {
+        self.simulation.get_field(&field.key).unwrap_or_default()
+    }
+
+    fn is_changed(&self) -> bool {
+        self.fields
+            .iter()
+            .any(|field| self.value(field) != field.original)
+    }
+
+    // Sets a field, keeping the previous value if the camera would not accept the result.
+    fn try_set(&mut self, key: &str, value: &str) -> anyhow::Result<()> {
+        let previous = self.simulation.get_field(key)?;
+        let result = self
+            .simulation
+            .set_field(key, value)
+            .and_then(|()| self.simulation.validate(self.model, self.values));
+
+        if result.is_err() {
+            self.simulation.set_field(key, &previous)?;
+        }
+
+        result
+    }
+
+    // Moves the selected field `steps` values forward or back, skipping any the model rejects.
+    fn cycle(&mut self, forward: bool, steps: usize) {
+        let field = &self.fields[self.selected];
+        if field.choices.is_empty() {
+            self.input = Some(self.value(field));
+            return;
+        }
+
+        let key = field.key.clone();
+        let choices = field.choices.clone();
+        let len = choices.len();
+        let current = self.value(field);
+        let start = choices.iter().position(|choice| *choice == current);
+
+        let mut last_error = None;
+        for attempt in 0..len {
+            let distance = (steps.min(len) + attempt) % len;
+            let index = match (start, forward) {
+                (None, _) => attempt,
+                (Some(start), true) => (start + distance) % len,
+                (Some(start), false) => (start + len - distance) % len,
+            };
+            if Some(index) == start {
+                continue;
+            }
+
+            match self.try_set(&key, &choices[index]) {
+                Ok(()) => return,
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        if let Some(e) = last_error {
+            self.message = Some(e.to_string());
+        }
+    }
+
+    fn revert(&mut self) {
+        let field = &self.fields[self.selected];
+        let (key, original) = (field.key.clone(), field.original.clone());
+        if let Err(e) = self.try_set(&key, &original) {
+            self.message = Some(e.to_string());
+        }
+    }
+
+    fn problems(&self) -> Vec<(Severity, String)> {
+        let mut problems: Vec<(Severity, String)> = rules::check_changes(
+            &self.original,
+            &self.simulation.to_recipe(),
+            self.model,
+            self.values,
+        )
+        .into_iter()
+        .map(|violation| (violation.severity, violation.message))
+        .collect();
+
+        if let Err(e) = self.simulation.validate(self.model, self.values) {
+            problems.push((Severity::Error, e.to_string()));
+        }
+
+        problems
+    }
+
+    fn run(&mut self, out: &mut impl Write) -> anyhow::Result<Outcome> {
+        loop {
+            self.draw(out)?;
+
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            if self.input.is_some() {
+                self.handle_input(key);
+                continue;
+            }
+
+            self.message = None;
+            let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+            match key.code {
+                KeyCode::Char('c') if ctrl => return Ok(Outcome::Quit),
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(Outcome::Quit),
+                KeyCode::Char('s') => {
+                    if self
+                        .problems()
+                        .iter()
+                        .any(|(severity, _)| *severity == Severity::Error)
+                    {
+                        self.message = Some(String::from("Fix the errors above before saving"));
+                    } else {
+                        return Ok(Outcome::Save);
+                    }
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.selected = self.selected.saturating_sub(1);
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    self.selected = (self.selected + 1).min(self.fields.len().saturating_sub(1));
+                }
+                KeyCode::Home => self.selected = 0,
+                KeyCode::End => self.selected = self.fields.len().saturating_sub(1),
+                KeyCode::Right | KeyCode::Char('l') => self.cycle(true, 1),
+                KeyCode::Left | KeyCode::Char('h') => self.cycle(false, 1),
+                KeyCode::PageUp => self.cycle(true, PAGE_STEPS),
+                KeyCode::PageDown => self.cycle(false, PAGE_STEPS),
+                KeyCode::Enter => {
+                    let field = &self.fields[self.selected];
+                    self.input = Some(self.value(field));
+                }
+                KeyCode::Char('r') => self.revert(),
+                _ => {}
+            }
+        }
+    }
+
+    fn handle_input(&mut self, key: KeyEvent) {
+        let Some(input) = &mut self.input else {
+            return;
+        };
+
+        match key.code {
+            KeyCode::Char(c) => input.push(c),
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Esc => self.input = None,
+            KeyCode::Enter => {
+                let value = input.clone();
+                let key = self.fields[self.selected].key.clone();
+                match self.try_set(&key, &value) {
+                    Ok(()) => {
+                        self.input = None;
+                        self.message = None;
+                    }
+                    Err(e) => self.message = Some(e.to_string()),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn draw(&mut self, out: &mut impl Write) -> anyhow::Result<()> {
+        let (_, rows) = terminal::size()?;
+        let problems = self.problems();
+
+        // Header and blank line, then the fields, then a blank line, problems, message and help.
+        let footer = 2 + problems.len() + usize::from(self.message.is_some());
+        let height = usize::from(rows).saturating_sub(2 + footer).max(1);
+        if self.selected < self.offset {
+            self.offset = self.selected;
+        } else if self.selected >= self.offset + height {
+            self.offset = self.selected + 1 - height;
+        }
+
+        let width = self
+            .fields
+            .iter()
+            .map(|field| field.label.len())
+            .max()
+            .unwrap_or_default();
+
+        queue!(
+            out,
+            terminal::Clear(ClearType::All),
+            cursor::MoveTo(0, 0),
+            SetAttribute(Attribute::Bold),
+            Print(format!("Editing {} on the {}", self.slot, self.model)),
+            SetAttribute(Attribute::Reset),
+        )?;
+
+        let mut row: u16 = 2;
+        for (index, field) in self
+            .fields
+            .iter()
+            .enumerate()
+            .skip(self.offset)
+            .take(height)
+        {
+            let selected = index == self.selected;
+            let value = match &self.input {
+                Some(input) if selected => format!("{input}_"),
+                _ => self.value(field),
+            };
+            let changed = value != field.original;
+
+            queue!(out, cursor::MoveTo(0, row))?;
+            if selected {
+                queue!(out, SetAttribute(Attribute::Reverse))?;
+            }
+            queue!(
+                out,
+                Print(format!("{:<width$}  ", field.label)),
+                SetAttribute(if changed {
+                    Attribute::Bold
+                } else {
+                    Attribute::NormalIntensity
+                }),
+                Print(
+                    if selected && self.input.is_none() && !field.choices.is_empty() {
+                        format!("< {value} >")
+                    } else {
+                        value
+                    }
+                ),
+                SetAttribute(Attribute::Reset),
+            )?;
+            row += 1;
+        }
+
+        row += 1;
+        for (severity, problem) in &problems {
+            let color = match severity {
+                Severity::Warning => Color::Yellow,
+                Severity::Error => Color::Red,
+            };
+            queue!(
+                out,
+                cursor::MoveTo(0, row),
+                SetForegroundColor(color),
+                Print(problem),
+                ResetColor,
+            )?;
+            row += 1;
+        }
+
+        if let Some(message) = &self.message {
+            queue!(
+                out,
+                cursor::MoveTo(0, row),
+                SetForegroundColor(Color::Red),
+                Print(message),
+                ResetColor,
+            )?;
+            row += 1;
+        }
+
+        let help = if self.input.is_some() {
+            "enter confirm  esc cancel"
+        } else {
+            "↑↓ select  ←→ change  pgup/pgdn ±10  enter type  r revert  s save  q quit"
+        };
+        queue!(
+            out,
+            cursor::MoveTo(0, row),
+            SetAttribute(Attribute::Dim),
+            Print(help),
+            SetAttribute(Attribute::Reset),
+        )?;
+
+        out.flush()?;
+        Ok(())
+    }
+}
+
+// Raw mode on the alternate screen, restored when dropped so errors do not leave the terminal
+// unusable.
+struct Screen;
+
+impl Screen {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(Self)
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub fn handle(options: GlobalOptions, slot: SlotRef) -> anyhow::Result<()> {
+    let GlobalOptions {
+        device,
+        emulate,
+        dry_run,
+        ..
+    } = options;
+
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        bail!(
+            "Editing a simulation requires an interactive terminal, use 'simulation set' instead"
+        );
+    }
+
+    let mut camera = usb::get_camera(device, emulate, dry_run)?;
+
+    let slot = slot.resolve(&mut camera)?;
+    let simulation = camera.get_simulation(slot)?;
+    let mut editor = Editor::new(camera.name(), camera.supported_values()?, slot, simulation)?;
+
+    let outcome = {
+        let _screen = Screen::enter()?;
+        editor.run(&mut io::stdout())?
+    };
+
+    match outcome {
+        Outcome::Quit => info!("Discarded changes to {slot}"),
+        Outcome::Save if !editor.is_changed() => info!("No changes to {slot}"),
+        // Only the changed properties are written, and the slot is restored if any write fails.
+        Outcome::Save => camera.set_simulation(slot, &*editor.simulation)?,
+    }
+
+    Ok(())
+}
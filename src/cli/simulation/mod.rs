@@ -1,8 +1,26 @@
-use fujicli::{features::simulation::SimulationListItem, ptp::fuji};
+mod edit;
+
+use std::{
+    io::IsTerminal,
+    path::{Path, PathBuf},
+};
+
+use anyhow::bail;
+use crossterm::style::Stylize;
+use fujicli::{
+    Camera,
+    features::simulation::{
+        FieldSelector, Recipe, RecipeLibrary, SimulationFormat, SimulationListEntry,
+        SimulationListItem, SlotDrift, SlotManifest, groups::filter_recipe,
+    },
+    ptp::fuji,
+};
 
 use super::common::{
     file::{Input, Output},
     film::FilmSimulationOptions,
+    slot::SlotRef,
+    source::SimulationSource,
 };
 use crate::cli::{GlobalOptions, common::usb};
 use clap::{Args, Subcommand};
@@ -11,20 +29,28 @@ use clap::{Args, Subcommand};
 pub enum SimulationCmd {
     /// List simulations
     #[command(alias = "l")]
-    List,
+    List {
+        /// Show every setting of every slot side by side
+        #[clap(long)]
+        full: bool,
+    },
 
     /// Get simulation
     #[command(alias = "g")]
     Get {
-        /// Simulation slot number
-        slot: fuji::CustomSetting,
+        /// Simulation slot number or name
+        slot: SlotRef,
+
+        /// Only print this field (e.g. highlight, white-balance)
+        #[clap(long)]
+        field: Option<String>,
     },
 
     /// Set simulation parameters
     #[command(alias = "s")]
     Set {
-        /// Simulation slot number
-        slot: fuji::CustomSetting,
+        /// Simulation slot number or name
+        slot: SlotRef,
 
         #[command(flatten)]
         set_film_simulation_options: SetFilmSimulationOptions,
@@ -33,44 +59,167 @@ pub enum SimulationCmd {
         film_simulation_options: FilmSimulationOptions,
     },
 
+    /// Edit a simulation interactively
+    #[command(alias = "ed")]
+    Edit {
+        /// Simulation slot number or name
+        slot: SlotRef,
+    },
+
+    /// Copy a simulation to another slot
+    #[command(alias = "cp")]
+    Copy {
+        /// Slot number or name to copy from
+        from: SlotRef,
+
+        /// Slot number or name to overwrite
+        to: SlotRef,
+    },
+
+    /// Swap the simulations of two slots
+    Swap {
+        /// First slot number or name
+        a: SlotRef,
+
+        /// Second slot number or name
+        b: SlotRef,
+    },
+
+    /// Move a simulation to another slot, resetting the original slot
+    #[command(alias = "mv")]
+    Move {
+        /// Slot number or name to move from
+        from: SlotRef,
+
+        /// Slot number or name to overwrite
+        to: SlotRef,
+    },
+
+    /// Restore a slot to its factory defaults
+    Reset {
+        /// Simulation slot number or name
+        slot: SlotRef,
+    },
+
     /// Export simulation
     #[command(alias = "e")]
     Export {
-        /// Simulation slot number
-        slot: fuji::CustomSetting,
+        /// Simulation slot number or name
+        slot: SlotRef,
 
         /// Output file (use '-' to write to stdout)
         output: Output,
+
+        /// File format (json, toml, yaml or text, detected from the extension by default)
+        #[clap(long)]
+        format: Option<SimulationFormat>,
+    },
+
+    /// Show the settings that differ between two simulations
+    #[command(alias = "d")]
+    Diff {
+        /// Simulation slot, exported file or image
+        left: SimulationSource,
+
+        /// Simulation slot, exported file or image
+        right: SimulationSource,
     },
 
     /// Import simulation
     #[command(alias = "i")]
     Import {
-        /// Simulation slot number
-        slot: fuji::CustomSetting,
+        /// Simulation slot number or name
+        slot: SlotRef,
+
+        /// Input file (use '-' to read from stdin)
+        input: Input,
+
+        /// File format (json, toml, yaml or text, detected from the extension by default)
+        #[clap(long)]
+        format: Option<SimulationFormat>,
 
+        #[command(flatten)]
+        fields: FieldSelectionOptions,
+    },
+
+    /// Export every slot into a single bundle
+    #[command(alias = "ea")]
+    ExportAll {
+        /// Output file (use '-' to write to stdout)
+        output: Output,
+
+        /// File format (json, toml or yaml, detected from the extension by default)
+        #[clap(long)]
+        format: Option<SimulationFormat>,
+    },
+
+    /// Import slots from a bundle
+    #[command(alias = "ia")]
+    ImportAll {
         /// Input file (use '-' to read from stdin)
         input: Input,
+
+        /// Only import this slot number or name (can be repeated, imports every slot by default)
+        #[clap(long = "slot")]
+        slots: Vec<SlotRef>,
+
+        /// File format (json, toml or yaml, detected from the extension by default)
+        #[clap(long)]
+        format: Option<SimulationFormat>,
+    },
+
+    /// Write the slots that differ from a manifest
+    Sync {
+        /// Manifest file describing the desired slots
+        manifest: PathBuf,
+
+        /// Report slots that differ from the manifest without writing them
+        #[clap(long)]
+        check: bool,
+
+        /// Manifest format (json, toml or yaml, detected from the extension by default)
+        #[clap(long)]
+        format: Option<SimulationFormat>,
     },
 }
 
+#[derive(Args, Debug)]
+pub struct FieldSelectionOptions {
+    /// Only apply these fields or groups (tone, color, white-balance, effects, output)
+    #[clap(long, value_delimiter = ',', conflicts_with = "except")]
+    pub only: Vec<FieldSelector>,
+
+    /// Apply every field except these fields or groups
+    #[clap(long, value_delimiter = ',')]
+    pub except: Vec<FieldSelector>,
+}
+
 #[derive(Args, Debug)]
 pub struct SetFilmSimulationOptions {
     /// The name of the slot
     #[clap(long)]
     pub name: Option<fuji::CustomSettingName>,
+
+    /// Apply a recipe from the library before the other settings
+    #[clap(long)]
+    pub recipe: Option<String>,
 }
 
 #[allow(clippy::needless_pass_by_value)]
-fn handle_list(options: GlobalOptions) -> anyhow::Result<()> {
+fn handle_list(options: GlobalOptions, full: bool) -> anyhow::Result<()> {
     let GlobalOptions {
         json,
         device,
         emulate,
+        dry_run,
         ..
     } = options;
 
-    let mut camera = usb::get_camera(device, emulate)?;
+    let mut camera = usb::get_camera(device, emulate, dry_run)?;
+
+    if full {
+        return handle_list_full(&mut camera, json);
+    }
 
     let slots: Vec<SimulationListItem> = camera
         .custom_settings_slots()?
@@ -93,19 +242,138 @@ fn handle_list(options: GlobalOptions) -> anyhow::Result<()> {
     Ok(())
 }
 
+fn handle_list_full(camera: &mut Camera, json: bool) -> anyhow::Result<()> {
+    let entries = camera
+        .custom_settings_slots()?
+        .into_iter()
+        .map(|slot| -> anyhow::Result<SimulationListEntry> {
+            let simulation = camera.get_simulation(slot)?;
+            Ok(SimulationListEntry { slot, simulation })
+        })
+        .collect::<anyhow::Result<Vec<SimulationListEntry>>>()?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+
+    let columns = entries
+        .iter()
+        .map(|entry| -> anyhow::Result<_> {
+            let defaults = camera.default_simulation(entry.slot)?.to_recipe().fields();
+            let recipe = entry.simulation.to_recipe();
+            let applied = recipe.without_ignored().fields();
+            Ok(recipe
+                .fields()
+                .into_iter()
+                .zip(applied)
+                .zip(defaults)
+                .map(|(((_, value), (_, applied)), (_, default))| {
+                    // Fields the slot's simulation or white balance ignores are never highlighted.
+                    let changed = applied.is_some() && value != default;
+                    (value.unwrap_or_else(|| String::from("-")), changed)
+                })
+                .collect::<Vec<_>>())
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let labels: Vec<&str> = Recipe::default()
+        .fields()
+        .into_iter()
+        .map(|(label, _)| label)
+        .collect();
+
+    print_table(
+        &labels,
+        &entries
+            .iter()
+            .map(|entry| entry.slot.to_string())
+            .collect::<Vec<_>>(),
+        &columns,
+    );
+
+    Ok(())
+}
+
+// Prints one row per field and one column per slot. Values that differ from the factory
+// defaults are shown in bold, or marked with '*' when the output is not a terminal.
+fn print_table(labels: &[&str], headers: &[String], columns: &[Vec<(String, bool)>]) {
+    let highlight = std::io::stdout().is_terminal();
+    let text_width = |text: &str| text.chars().count();
+
+    let label_width = labels
+        .iter()
+        .map(|label| text_width(label))
+        .max()
+        .unwrap_or(0);
+    let widths: Vec<usize> = headers
+        .iter()
+        .zip(columns)
+        .map(|(header, column)| {
+            column
+                .iter()
+                .map(|(value, changed)| text_width(value) + usize::from(*changed && !highlight))
+                .chain([text_width(header)])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let mut cells = vec![format!("{:label_width$}", "")];
+    cells.extend(
+        headers
+            .iter()
+            .zip(&widths)
+            .map(|(header, width)| format!("{header:width$}")),
+    );
+    println!("{}", cells.join("  ").trim_end());
+
+    for (row, label) in labels.iter().enumerate() {
+        let mut cells = vec![format!("{label:label_width$}")];
+        cells.extend(columns.iter().zip(&widths).map(|(column, width)| {
+            let (value, changed) = &column[row];
+            let value = if *changed && !highlight {
+                format!("{value}*")
+            } else {
+                value.clone()
+            };
+            let padding = " ".repeat(width - text_width(&value));
+
+            if *changed && highlight {
+                format!("{}{padding}", value.as_str().bold())
+            } else {
+                format!("{value}{padding}")
+            }
+        }));
+        println!("{}", cells.join("  ").trim_end());
+    }
+}
+
 #[allow(clippy::needless_pass_by_value)]
-fn handle_get(options: GlobalOptions, slot: fuji::CustomSetting) -> anyhow::Result<()> {
+fn handle_get(options: GlobalOptions, slot: SlotRef, field: Option<&str>) -> anyhow::Result<()> {
     let GlobalOptions {
         json,
         device,
         emulate,
+        dry_run,
         ..
     } = options;
 
-    let mut camera = usb::get_camera(device, emulate)?;
+    let mut camera = usb::get_camera(device, emulate, dry_run)?;
 
+    let slot = slot.resolve(&mut camera)?;
     let simulation = camera.get_simulation(slot)?;
 
+    if let Some(field) = field {
+        let value = simulation.get_field(field)?;
+        if json {
+            println!("{}", serde_json::to_string_pretty(&value)?);
+        } else {
+            println!("{value}");
+        }
+        return Ok(());
+    }
+
     if json {
         println!("{}", serde_json::to_string_pretty(&simulation)?);
     } else {
@@ -115,89 +383,43 @@ fn handle_get(options: GlobalOptions, slot: fuji::CustomSetting) -> anyhow::Resu
     Ok(())
 }
 
-macro_rules! update_simulation {
-    ($sim_var:ident, [
-        $($local_field:ident => $setter:ident,)*
-    ]) => {
-        $(
-            if let Some(value) = $local_field {
-                $sim_var.$setter(value)?;
-            }
-        )*
-    };
-}
-
 #[allow(clippy::needless_pass_by_value)]
 fn handle_set(
     options: GlobalOptions,
     set_options: &SetFilmSimulationOptions,
     film_options: &FilmSimulationOptions,
-    slot: fuji::CustomSetting,
+    slot: SlotRef,
 ) -> anyhow::Result<()> {
     let GlobalOptions {
-        device, emulate, ..
+        device,
+        emulate,
+        dry_run,
+        ..
     } = options;
 
-    let mut camera = usb::get_camera(device, emulate)?;
-
-    let SetFilmSimulationOptions { name } = set_options;
-
-    let FilmSimulationOptions {
-        simulation: film_simulation,
-        monochromatic_color_temperature,
-        monochromatic_color_tint,
-        size,
-        quality,
-        highlight,
-        shadow,
-        color,
-        sharpness,
-        clarity,
-        white_balance,
-        white_balance_shift_red,
-        white_balance_shift_blue,
-        white_balance_temperature,
-        dynamic_range,
-        dynamic_range_priority,
-        noise_reduction,
-        grain,
-        color_chrome_effect,
-        color_chrome_fx_blue,
-        smooth_skin_effect,
-        lens_modulation_optimizer,
-        color_space,
-    } = film_options;
+    let mut camera = usb::get_camera(device, emulate, dry_run)?;
+
+    let SetFilmSimulationOptions { name, recipe } = set_options;
+
+    let recipe = recipe
+        .as_deref()
+        .map(|recipe| RecipeLibrary::open_default()?.get(recipe))
+        .transpose()?;
+    let model = camera.name();
+    let values = camera.supported_values()?;
 
+    let mut options = film_options.to_recipe(model, values)?;
+    options.name.clone_from(name);
+
+    let slot = slot.resolve(&mut camera)?;
     camera.update_simulation(slot, &mut |simulation| {
-        update_simulation! {
-            simulation,
-            [
-                name => set_name,
-                film_simulation => set_simulation,
-                monochromatic_color_temperature => set_monochromatic_color_temperature,
-                monochromatic_color_tint => set_monochromatic_color_tint,
-                size => set_size,
-                quality => set_quality,
-                highlight => set_highlight,
-                shadow => set_shadow,
-                color => set_color,
-                sharpness => set_sharpness,
-                clarity => set_clarity,
-                white_balance => set_white_balance,
-                white_balance_shift_red => set_white_balance_shift_red,
-                white_balance_shift_blue => set_white_balance_shift_blue,
-                white_balance_temperature => set_white_balance_temperature,
-                dynamic_range => set_dynamic_range,
-                dynamic_range_priority => set_dynamic_range_priority,
-                noise_reduction => set_noise_reduction,
-                grain => set_grain,
-                color_chrome_effect => set_color_chrome_effect,
-                color_chrome_fx_blue => set_color_chrome_fx_blue,
-                smooth_skin_effect => set_smooth_skin_effect,
-                lens_modulation_optimizer => set_lens_modulation_optimizer,
-                color_space => set_color_space,
-            ]
-        };
+        if let Some(recipe) = &recipe {
+            recipe
+                .recipe
+                .apply_to_simulation(simulation, model, values)?;
+        }
+
+        options.set_on_simulation(simulation)?;
 
         Ok(())
     })?;
@@ -205,21 +427,82 @@ fn handle_set(
     Ok(())
 }
 
+#[derive(Debug, Clone, Copy)]
+enum SlotOperation {
+    Copy,
+    Swap,
+    Move,
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn handle_slot_operation(
+    options: GlobalOptions,
+    operation: SlotOperation,
+    a: SlotRef,
+    b: SlotRef,
+) -> anyhow::Result<()> {
+    let GlobalOptions {
+        device,
+        emulate,
+        dry_run,
+        ..
+    } = options;
+
+    if a == b {
+        bail!("Both slots are {a}");
+    }
+
+    let mut camera = usb::get_camera(device, emulate, dry_run)?;
+
+    let a = a.resolve(&mut camera)?;
+    let b = b.resolve(&mut camera)?;
+    if a == b {
+        bail!("Both slots are {a}");
+    }
+
+    match operation {
+        SlotOperation::Copy => camera.copy_simulation(a, b),
+        SlotOperation::Swap => camera.swap_simulations(a, b),
+        SlotOperation::Move => camera.move_simulation(a, b),
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn handle_reset(options: GlobalOptions, slot: SlotRef) -> anyhow::Result<()> {
+    let GlobalOptions {
+        device,
+        emulate,
+        dry_run,
+        ..
+    } = options;
+
+    let mut camera = usb::get_camera(device, emulate, dry_run)?;
+    let slot = slot.resolve(&mut camera)?;
+    camera.reset_simulation(slot)
+}
+
 #[allow(clippy::needless_pass_by_value)]
 fn handle_export(
     options: GlobalOptions,
-    slot: fuji::CustomSetting,
+    slot: SlotRef,
     output: Output,
+    format: Option<SimulationFormat>,
 ) -> anyhow::Result<()> {
     let GlobalOptions {
-        device, emulate, ..
+        device,
+        emulate,
+        dry_run,
+        ..
     } = options;
 
-    let mut camera = usb::get_camera(device, emulate)?;
+    let mut camera = usb::get_camera(device, emulate, dry_run)?;
+
+    let format = SimulationFormat::detect(format, output.path());
 
+    let slot = slot.resolve(&mut camera)?;
     let mut writer = output.get_writer()?;
     let simulation = camera.get_simulation(slot)?;
-    let simulation = camera.serialize_simulation(&*simulation)?;
+    let simulation = camera.serialize_simulation(&*simulation, format)?;
     writer.write_all(&simulation)?;
 
     Ok(())
@@ -228,28 +511,237 @@ fn handle_export(
 #[allow(clippy::needless_pass_by_value)]
 fn handle_import(
     options: GlobalOptions,
-    slot: fuji::CustomSetting,
+    slot: SlotRef,
     input: Input,
+    format: Option<SimulationFormat>,
+    fields: &FieldSelectionOptions,
 ) -> anyhow::Result<()> {
     let GlobalOptions {
-        device, emulate, ..
+        device,
+        emulate,
+        dry_run,
+        ..
     } = options;
 
-    let mut camera = usb::get_camera(device, emulate)?;
+    let mut camera = usb::get_camera(device, emulate, dry_run)?;
+
+    let format = SimulationFormat::detect(format, input.path());
+    let base_dir = input.path().and_then(Path::parent).map(Path::to_path_buf);
 
     let mut reader = input.get_reader()?;
     let mut simulation = Vec::new();
     reader.read_to_end(&mut simulation)?;
-    let simulation = camera.deserialize_simulation(&simulation)?;
-    camera.set_simulation(slot, &*simulation)?;
+    let simulation = camera.deserialize_simulation(&simulation, format, base_dir.as_deref())?;
+    let slot = slot.resolve(&mut camera)?;
+
+    if fields.only.is_empty() && fields.except.is_empty() {
+        camera.set_simulation(slot, &*simulation)?;
+    } else {
+        let mut recipe = simulation.to_recipe();
+        filter_recipe(&mut recipe, &fields.only, &fields.except);
+
+        let model = camera.name();
+        let values = camera.supported_values()?;
+        camera.update_simulation(slot, &mut |simulation| {
+            recipe.apply_to_simulation(simulation, model, values)
+        })?;
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn handle_export_all(
+    options: GlobalOptions,
+    output: Output,
+    format: Option<SimulationFormat>,
+) -> anyhow::Result<()> {
+    let GlobalOptions {
+        device,
+        emulate,
+        dry_run,
+        ..
+    } = options;
+
+    let mut camera = usb::get_camera(device, emulate, dry_run)?;
+
+    let format = SimulationFormat::detect(format, output.path());
+
+    let bundle = camera.export_simulations(format)?;
+    let mut writer = output.get_writer()?;
+    writer.write_all(&bundle)?;
+
+    Ok(())
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn handle_import_all(
+    options: GlobalOptions,
+    input: Input,
+    slots: &[SlotRef],
+    format: Option<SimulationFormat>,
+) -> anyhow::Result<()> {
+    let GlobalOptions {
+        device,
+        emulate,
+        dry_run,
+        ..
+    } = options;
+
+    let mut camera = usb::get_camera(device, emulate, dry_run)?;
+
+    let format = SimulationFormat::detect(format, input.path());
+
+    let mut reader = input.get_reader()?;
+    let mut bundle = Vec::new();
+    reader.read_to_end(&mut bundle)?;
+    let slots = slots
+        .iter()
+        .map(|slot| slot.resolve(&mut camera))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let changed = camera.import_simulations(&bundle, format, &slots)?;
+
+    if changed.is_empty() {
+        println!("All slots already match the bundle");
+    } else {
+        for drift in changed {
+            println!("- {}", drift.slot);
+        }
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn handle_diff(
+    options: GlobalOptions,
+    left: &SimulationSource,
+    right: &SimulationSource,
+) -> anyhow::Result<()> {
+    let GlobalOptions {
+        json,
+        device,
+        emulate,
+        dry_run,
+        ..
+    } = options;
+
+    let mut camera = if left.is_slot() || right.is_slot() {
+        Some(usb::get_camera(device, emulate, dry_run)?)
+    } else {
+        None
+    };
+
+    let left_recipe = left.load(camera.as_mut())?;
+    let right_recipe = right.load(camera.as_mut())?;
+    let differences = left_recipe.diff(&right_recipe);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&differences)?);
+        return Ok(());
+    }
+
+    if differences.is_empty() {
+        println!("No differences");
+        return Ok(());
+    }
+
+    let rows: Vec<[String; 3]> = differences
+        .into_iter()
+        .map(|d| {
+            [
+                d.field.to_string(),
+                d.left.unwrap_or_else(|| "-".to_string()),
+                d.right.unwrap_or_else(|| "-".to_string()),
+            ]
+        })
+        .collect();
+
+    let header = [String::from("Field"), left.to_string(), right.to_string()];
+    let widths: Vec<usize> = (0..3)
+        .map(|i| {
+            rows.iter()
+                .chain(std::iter::once(&header))
+                .map(|row| row[i].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    for row in std::iter::once(&header).chain(rows.iter()) {
+        println!(
+            "{:<w0$}  {:<w1$}  {}",
+            row[0],
+            row[1],
+            row[2],
+            w0 = widths[0],
+            w1 = widths[1]
+        );
+    }
+
+    Ok(())
+}
+
+fn print_drift(plan: &[SlotDrift]) {
+    for drift in plan {
+        println!("{}:", drift.slot);
+        for difference in &drift.differences {
+            println!(
+                "  {}: {} -> {}",
+                difference.field,
+                difference.left.as_deref().unwrap_or("-"),
+                difference.right.as_deref().unwrap_or("-")
+            );
+        }
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn handle_sync(
+    options: GlobalOptions,
+    manifest: &Path,
+    check: bool,
+    format: Option<SimulationFormat>,
+) -> anyhow::Result<()> {
+    let GlobalOptions {
+        json,
+        device,
+        emulate,
+        dry_run,
+        ..
+    } = options;
+
+    let base_dir = manifest.parent().unwrap_or_else(|| Path::new("."));
+    let desired =
+        SlotManifest::load(manifest, format)?.resolve(&RecipeLibrary::open_default()?, base_dir)?;
+
+    let mut camera = usb::get_camera(device, emulate, dry_run)?;
+    let plan = camera.plan_simulation_sync(&desired)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&plan)?);
+    } else if plan.is_empty() {
+        println!("All slots match the manifest");
+    } else {
+        print_drift(&plan);
+    }
+
+    if check {
+        if !plan.is_empty() {
+            bail!("{} slot(s) differ from the manifest", plan.len());
+        }
+        return Ok(());
+    }
+
+    camera.apply_simulation_sync(&plan)?;
 
     Ok(())
 }
 
 pub fn handle(cmd: SimulationCmd, options: GlobalOptions) -> anyhow::Result<()> {
     match cmd {
-        SimulationCmd::List => handle_list(options),
-        SimulationCmd::Get { slot } => handle_get(options, slot),
+        SimulationCmd::List { full } => handle_list(options, full),
+        SimulationCmd::Get { slot, field } => handle_get(options, slot, field.as_deref()),
         SimulationCmd::Set {
             slot,
             set_film_simulation_options,
@@ -260,7 +752,37 @@ pub fn handle(cmd: SimulationCmd, options: GlobalOptions) -> anyhow::Result<()>
             &film_simulation_options,
             slot,
         ),
-        SimulationCmd::Export { slot, output } => handle_export(options, slot, output),
-        SimulationCmd::Import { slot, input } => handle_import(options, slot, input),
+        SimulationCmd::Edit { slot } => edit::handle(options, slot),
+        SimulationCmd::Copy { from, to } => {
+            handle_slot_operation(options, SlotOperation::Copy, from, to)
+        }
+        SimulationCmd::Swap { a, b } => handle_slot_operation(options, SlotOperation::Swap, a, b),
+        SimulationCmd::Move { from, to } => {
+            handle_slot_operation(options, SlotOperation::Move, from, to)
+        }
+        SimulationCmd::Reset { slot } => handle_reset(options, slot),
+        SimulationCmd::Export {
+            slot,
+            output,
+            format,
+        } => handle_export(options, slot, output, format),
+        SimulationCmd::Diff { left, right } => handle_diff(options, &left, &right),
+        SimulationCmd::Import {
+            slot,
+            input,
+            format,
+            fields,
+        } => handle_import(options, slot, input, format, &fields),
+        SimulationCmd::ExportAll { output, format } => handle_export_all(options, output, format),
+        SimulationCmd::ImportAll {
+            input,
+            slots,
+            format,
+        } => handle_import_all(options, input, &slots, format),
+        SimulationCmd::Sync {
+            manifest,
+            check,
+            format,
+        } => handle_sync(options, &manifest, check, format),
     }
 }
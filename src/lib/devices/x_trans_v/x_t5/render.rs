@@ -1,22 +1,50 @@
-use std::io::{self, Cursor, Write};
+use std::io::{self, Cursor};
 
-use log::warn;
-use ptp_cursor::{ExactString, PtpDeserialize, PtpSerialize};
+use ptp_cursor::{ExactString, LayoutReader, PtpDeserialize, PtpLayout, PtpSerialize};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    devices::x_trans_v::x_t5::{FujifilmXT5, simulation::XT5Simulation},
+    devices::x_trans_v::x_t5::{FUJIFILM_X_T5, FujifilmXT5, simulation::SUPPORTED_VALUES},
     features::{
         render::{CameraRenderManager, ConversionProfile},
-        simulation::Simulation,
+        simulation::{Recipe, Simulation, rules},
     },
-    ptp::{DevicePropCode, Ptp, fuji},
+    ptp::{DevicePropCode, Ptp, fuji, trace_layout},
 };
 
 impl XT5ConversionProfile {
     const EXPECTED_N_PROPS: i16 = 29;
     const EXPECTED_PROFILE_CODE: u32 = 0xff17_9502;
     const PADDING: usize = 0x1EE;
+
+    fn to_recipe(&self) -> Recipe {
+        Recipe {
+            name: None,
+            size: Some(self.size),
+            quality: Some(self.quality),
+            simulation: Some(self.simulation),
+            monochromatic_color_temperature: Some(self.monochromatic_color_temperature),
+            monochromatic_color_tint: Some(self.monochromatic_color_tint),
+            highlight: Some(self.highlight),
+            shadow: Some(self.shadow),
+            color: Some(self.color),
+            sharpness: Some(self.sharpness),
+            clarity: Some(self.clarity),
+            noise_reduction: Some(self.noise_reduction),
+            grain: Some(self.grain),
+            color_chrome_effect: Some(self.color_chrome_effect),
+            color_chrome_fx_blue: Some(self.color_chrome_fx_blue),
+            smooth_skin_effect: Some(self.smooth_skin_effect),
+            white_balance: Some(self.white_balance),
+            white_balance_shift_red: Some(self.white_balance_shift_red),
+            white_balance_shift_blue: Some(self.white_balance_shift_blue),
+            white_balance_temperature: self.white_balance_temperature,
+            dynamic_range: Some(self.dynamic_range),
+            dynamic_range_priority: Some(self.dynamic_range_priority),
+            lens_modulation_optimizer: Some(self.lens_modulation_optimizer),
+            color_space: Some(self.color_space),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -194,13 +222,7 @@ impl PtpDeserialize for XT5ConversionProfile {
 }
 
 impl PtpSerialize for XT5ConversionProfile {
-    fn try_into_ptp(&self) -> io::Result<Vec<u8>> {
-        let mut buf = Vec::new();
-        self.try_write_ptp(&mut buf)?;
-        Ok(buf)
-    }
-
-    fn try_write_ptp(&self, buf: &mut Vec<u8>) -> io::Result<()> {
+    fn try_write_ptp<W: ptp_cursor::Write + ?Sized>(&self, buf: &mut W) -> io::Result<()> {
         Self::EXPECTED_N_PROPS.try_write_ptp(buf)?;
 
         let profile_code = format!("{:x}", Self::EXPECTED_PROFILE_CODE);
@@ -220,13 +242,6 @@ impl PtpSerialize for XT5ConversionProfile {
         u32::from(self.simulation).try_write_ptp(buf)?;
         u32::from(self.grain).try_write_ptp(buf)?;
         u32::from(self.color_chrome_effect).try_write_ptp(buf)?;
-        if self.white_balance_as_shot == fuji::WhiteBalanceAsShot::False
-            && self.white_balance == fuji::WhiteBalance::AsShot
-        {
-            warn!(
-                "White Balance has been altered but no explicit White Balance mode has been set. Consider setting a White Balance mode."
-            );
-        }
         u32::from(self.white_balance_as_shot).try_write_ptp(buf)?;
         u32::from(self.white_balance).try_write_ptp(buf)?;
         i32::from(self.white_balance_shift_red).try_write_ptp(buf)?;
@@ -255,39 +270,57 @@ impl PtpSerialize for XT5ConversionProfile {
     }
 }
 
-impl ConversionProfile for XT5ConversionProfile {
-    fn set_from_simulation(&mut self, simulation: &dyn Simulation) -> anyhow::Result<()> {
-        let simulation = simulation
-            .as_any()
-            .downcast_ref::<XT5Simulation>()
-            .expect("Simulation type mismatch");
-
-        self.set_size(&simulation.size)?;
-        self.set_quality(&simulation.quality)?;
-        self.set_simulation(&simulation.simulation)?;
-        self.set_monochromatic_color_temperature(&simulation.monochromatic_color_temperature)?;
-        self.set_monochromatic_color_tint(&simulation.monochromatic_color_tint)?;
-        self.set_highlight(&simulation.highlight)?;
-        self.set_shadow(&simulation.shadow)?;
-        self.set_color(&simulation.color)?;
-        self.set_sharpness(&simulation.sharpness)?;
-        self.set_clarity(&simulation.clarity)?;
-        self.set_white_balance(&simulation.white_balance)?;
-        self.set_white_balance_shift_red(&simulation.white_balance_shift_red)?;
-        self.set_white_balance_shift_blue(&simulation.white_balance_shift_blue)?;
-        self.set_white_balance_temperature(&simulation.white_balance_temperature)?;
-        self.set_dynamic_range(&simulation.dynamic_range)?;
-        self.set_dynamic_range_priority(&simulation.dynamic_range_priority)?;
-        self.set_noise_reduction(&simulation.noise_reduction)?;
-        self.set_grain(&simulation.grain)?;
-        self.set_color_chrome_effect(&simulation.color_chrome_effect)?;
-        self.set_color_chrome_fx_blue(&simulation.color_chrome_fx_blue)?;
-        self.set_smooth_skin_effect(&simulation.smooth_skin_effect)?;
-        self.set_lens_modulation_optimizer(&simulation.lens_modulation_optimizer)?;
-        self.set_color_space(&simulation.color_space)?;
+impl PtpLayout for XT5ConversionProfile {
+    fn read_ptp_layout(reader: &mut LayoutReader<'_>) -> io::Result<()> {
+        reader.field::<i16>("n_props")?;
+        reader.field::<ExactString>("profile_code")?;
+        reader.padding("padding", Self::PADDING)?;
+
+        reader.unknown::<i32>("unknown_0")?;
+        reader.field_as::<u32, fuji::FileType>("file_type")?;
+        reader.field_as::<u32, fuji::ImageSize>("size")?;
+        reader.field_as::<u32, fuji::ImageQuality>("quality")?;
+        reader.field_as::<i32, fuji::ExposureOffset>("exposure_offset")?;
+        reader.field_as::<u32, fuji::DynamicRange>("dynamic_range")?;
+        reader.field_as::<u32, fuji::DynamicRangePriority>("dynamic_range_priority")?;
+        reader.field_as::<u32, fuji::FilmSimulation>("simulation")?;
+        reader.field_as::<u32, fuji::GrainEffect>("grain")?;
+        reader.field_as::<u32, fuji::ColorChromeEffect>("color_chrome_effect")?;
+        reader.field_as::<u32, fuji::WhiteBalanceAsShot>("white_balance_as_shot")?;
+        reader.field_as::<u32, fuji::WhiteBalance>("white_balance")?;
+        reader.field_as::<i32, fuji::WhiteBalanceShift>("white_balance_shift_red")?;
+        reader.field_as::<i32, fuji::WhiteBalanceShift>("white_balance_shift_blue")?;
+        reader.field_as::<i32, fuji::WhiteBalanceTemperature>("white_balance_temperature")?;
+        reader.field_as::<i32, fuji::HighlightTone>("highlight")?;
+        reader.field_as::<i32, fuji::ShadowTone>("shadow")?;
+        reader.field_as::<i32, fuji::Color>("color")?;
+        reader.field_as::<i32, fuji::Sharpness>("sharpness")?;
+        reader.field_as::<u32, fuji::NoiseReduction>("noise_reduction")?;
+        reader.field_as::<u32, fuji::LensModulationOptimizer>("lens_modulation_optimizer")?;
+        reader.field_as::<u32, fuji::ColorSpace>("color_space")?;
+        reader.field_as::<i32, fuji::MonochromaticColorShift>("monochromatic_color_temperature")?;
+        reader.field_as::<u32, fuji::SmoothSkinEffect>("smooth_skin_effect")?;
+        reader.field_as::<u32, fuji::ColorChromeFXBlue>("color_chrome_fx_blue")?;
+        reader.field_as::<i32, fuji::MonochromaticColorShift>("monochromatic_color_tint")?;
+        reader.field_as::<i32, fuji::Clarity>("clarity")?;
+        reader.field_as::<u32, fuji::Teleconverter>("teleconverter")?;
+
+        // Only present in profiles we send, see `try_write_ptp`.
+        let cur = reader.cursor();
+        if cur.position() < cur.get_ref().len() as u64 {
+            reader.unknown::<i32>("unknown_1")?;
+        }
 
         Ok(())
     }
+}
+
+impl ConversionProfile for XT5ConversionProfile {
+    fn set_from_simulation(&mut self, simulation: &dyn Simulation) -> anyhow::Result<()> {
+        simulation
+            .to_recipe()
+            .apply_to_profile(self, FUJIFILM_X_T5.name, &SUPPORTED_VALUES)
+    }
 
     fn set_file_type(&mut self, value: &fuji::FileType) -> anyhow::Result<()> {
         self.file_type = *value;
@@ -438,13 +471,7 @@ impl ConversionProfile for XT5ConversionProfile {
     }
 
     fn set_dynamic_range(&mut self, value: &fuji::DynamicRange) -> anyhow::Result<()> {
-        if *value == fuji::DynamicRange::HDR800Plus {
-            self.dynamic_range = fuji::DynamicRange::HDR800;
-            self.dynamic_range_priority = fuji::DynamicRangePriority::Plus;
-        } else {
-            self.dynamic_range = *value;
-        }
-
+        self.dynamic_range = *value;
         Ok(())
     }
 
@@ -482,10 +509,22 @@ impl CameraRenderManager for FujifilmXT5 {
     ) -> anyhow::Result<Vec<u8>> {
         self.send_image(ptp, image)?;
 
-        let mut profile: XT5ConversionProfile =
-            ptp.get_prop(DevicePropCode::FujiRawConversionProfile)?;
+        let profile = ptp.get_prop_raw(DevicePropCode::FujiRawConversionProfile)?;
+        trace_layout::<XT5ConversionProfile>("Incoming conversion profile", &profile);
+
+        let mut profile = XT5ConversionProfile::try_from_ptp(&profile)?;
+        let original = profile.to_recipe();
         conversion_profile_modifier(&mut profile)?;
-        ptp.set_prop(DevicePropCode::FujiRawConversionProfile, &profile)?;
+        rules::enforce(&rules::check_changes(
+            &original,
+            &profile.to_recipe(),
+            FUJIFILM_X_T5.name,
+            &SUPPORTED_VALUES,
+        ))?;
+
+        let profile = profile.try_into_ptp()?;
+        trace_layout::<XT5ConversionProfile>("Outgoing conversion profile", &profile);
+        ptp.set_prop_raw(DevicePropCode::FujiRawConversionProfile, &profile)?;
 
         self.render_image(ptp, draft)
     }
@@ -1,14 +1,15 @@
 use std::fmt;
 
-use log::error;
+use anyhow::anyhow;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    devices::x_trans_v::x_t5::FujifilmXT5,
+    devices::x_trans_v::x_t5::{FUJIFILM_X_T5, FujifilmXT5},
     features::simulation::{
-        CameraSimulationManager, CameraSimulationParser, Simulation, define_simulation,
+        CameraSimulationManager, CameraSimulationParser, ModelImageSize, Simulation,
+        SupportedValues, define_simulation,
     },
-    ptp::{DevicePropCode, Ptp, fuji},
+    ptp::{DevicePropCode, PropSource, PropWrite, Ptp, fuji},
 };
 
 define_simulation!(
@@ -38,7 +39,114 @@ define_simulation!(
     ]
 );
 
+pub(super) static SUPPORTED_VALUES: SupportedValues = SupportedValues {
+    image_sizes: Some(&[
+        ModelImageSize {
+            size: fuji::ImageSize::L3x2,
+            width: 7728,
+            height: 5152,
+        },
+        ModelImageSize {
+            size: fuji::ImageSize::L16x9,
+            width: 7728,
+            height: 4344,
+        },
+        ModelImageSize {
+            size: fuji::ImageSize::L1x1,
+            width: 5152,
+            height: 5152,
+        },
+        ModelImageSize {
+            size: fuji::ImageSize::L4x3,
+            width: 6864,
+            height: 5152,
+        },
+        ModelImageSize {
+            size: fuji::ImageSize::L5x4,
+            width: 6432,
+            height: 5152,
+        },
+        ModelImageSize {
+            size: fuji::ImageSize::M3x2,
+            width: 5472,
+            height: 3648,
+        },
+        ModelImageSize {
+            size: fuji::ImageSize::M16x9,
+            width: 5472,
+            height: 3080,
+        },
+        ModelImageSize {
+            size: fuji::ImageSize::M1x1,
+            width: 3648,
+            height: 3648,
+        },
+        ModelImageSize {
+            size: fuji::ImageSize::M4x3,
+            width: 4864,
+            height: 3648,
+        },
+        ModelImageSize {
+            size: fuji::ImageSize::M5x4,
+            width: 4560,
+            height: 3648,
+        },
+        ModelImageSize {
+            size: fuji::ImageSize::S3x2,
+            width: 3888,
+            height: 2592,
+        },
+        ModelImageSize {
+            size: fuji::ImageSize::S16x9,
+            width: 3888,
+            height: 2184,
+        },
+        ModelImageSize {
+            size: fuji::ImageSize::S1x1,
+            width: 2592,
+            height: 2592,
+        },
+        ModelImageSize {
+            size: fuji::ImageSize::S4x3,
+            width: 3456,
+            height: 2592,
+        },
+        ModelImageSize {
+            size: fuji::ImageSize::S5x4,
+            width: 3264,
+            height: 2592,
+        },
+    ]),
+    film_simulations: Some(&[
+        fuji::FilmSimulation::Provia,
+        fuji::FilmSimulation::Velvia,
+        fuji::FilmSimulation::Astia,
+        fuji::FilmSimulation::PRONegHi,
+        fuji::FilmSimulation::PRONegStd,
+        fuji::FilmSimulation::Monochrome,
+        fuji::FilmSimulation::MonochromeYe,
+        fuji::FilmSimulation::MonochromeR,
+        fuji::FilmSimulation::MonochromeG,
+        fuji::FilmSimulation::Sepia,
+        fuji::FilmSimulation::ClassicChrome,
+        fuji::FilmSimulation::AcrosSTD,
+        fuji::FilmSimulation::AcrosYe,
+        fuji::FilmSimulation::AcrosR,
+        fuji::FilmSimulation::AcrosG,
+        fuji::FilmSimulation::Eterna,
+        fuji::FilmSimulation::ClassicNegative,
+        fuji::FilmSimulation::NostalgicNegative,
+        fuji::FilmSimulation::EternaBleachBypass,
+    ]),
+    base_iso: Some(125),
+    ..SupportedValues::ANY
+};
+
 impl CameraSimulationParser for FujifilmXT5 {
+    fn supported_values(&self) -> &'static SupportedValues {
+        &SUPPORTED_VALUES
+    }
+
     fn deserialize_simulation(&self, simulation: &[u8]) -> anyhow::Result<Box<dyn Simulation>> {
         let simulation: XT5Simulation = serde_json::from_slice(simulation)?;
         Ok(Box::new(simulation))
@@ -56,71 +164,16 @@ impl CameraSimulationManager for FujifilmXT5 {
         slot: fuji::CustomSetting,
     ) -> anyhow::Result<Box<dyn Simulation>> {
         ptp.set_prop(DevicePropCode::FujiCustomSetting, &slot)?;
+        Ok(Box::new(read_simulation(ptp, PropSource::Current)?))
+    }
 
-        let name = ptp.get_prop(DevicePropCode::FujiCustomSettingName)?;
-        let size = ptp.get_prop(DevicePropCode::FujiCustomSettingImageSize)?;
-        let quality = ptp.get_prop(DevicePropCode::FujiCustomSettingImageQuality)?;
-        let simulation: fuji::FilmSimulation =
-            ptp.get_prop(DevicePropCode::FujiCustomSettingFilmSimulation)?;
-        let monochromatic_color_temperature =
-            ptp.get_prop(DevicePropCode::FujiCustomSettingMonochromaticColorTemperature)?;
-        let monochromatic_color_tint =
-            ptp.get_prop(DevicePropCode::FujiCustomSettingMonochromaticColorTint)?;
-        let dynamic_range_priority =
-            ptp.get_prop(DevicePropCode::FujiCustomSettingDynamicRangePriority)?;
-        let dynamic_range = ptp.get_prop(DevicePropCode::FujiCustomSettingDynamicRange)?;
-        let highlight = ptp.get_prop(DevicePropCode::FujiCustomSettingHighlightTone)?;
-        let shadow = ptp.get_prop(DevicePropCode::FujiCustomSettingShadowTone)?;
-        let color = ptp.get_prop(DevicePropCode::FujiCustomSettingColor)?;
-        let sharpness = ptp.get_prop(DevicePropCode::FujiCustomSettingSharpness)?;
-        let clarity = ptp.get_prop(DevicePropCode::FujiCustomSettingClarity)?;
-        let noise_reduction = ptp.get_prop(DevicePropCode::FujiCustomSettingHighISONR)?;
-        let grain = ptp.get_prop(DevicePropCode::FujiCustomSettingGrainEffect)?;
-        let color_chrome_effect =
-            ptp.get_prop(DevicePropCode::FujiCustomSettingColorChromeEffect)?;
-        let color_chrome_fx_blue =
-            ptp.get_prop(DevicePropCode::FujiCustomSettingColorChromeFXBlue)?;
-        let smooth_skin_effect = ptp.get_prop(DevicePropCode::FujiCustomSettingSmoothSkinEffect)?;
-        let white_balance =
-            ptp.get_prop::<fuji::WhiteBalance>(DevicePropCode::FujiCustomSettingWhiteBalance)?;
-        let white_balance_shift_red =
-            ptp.get_prop(DevicePropCode::FujiCustomSettingWhiteBalanceShiftRed)?;
-        let white_balance_shift_blue =
-            ptp.get_prop(DevicePropCode::FujiCustomSettingWhiteBalanceShiftBlue)?;
-        let white_balance_temperature =
-            ptp.get_prop(DevicePropCode::FujiCustomSettingWhiteBalanceTemperature)?;
-        let lens_modulation_optimizer =
-            ptp.get_prop(DevicePropCode::FujiCustomSettingLensModulationOptimizer)?;
-        let color_space = ptp.get_prop(DevicePropCode::FujiCustomSettingColorSpace)?;
-
-        let sim = XT5Simulation {
-            name,
-            size,
-            quality,
-            simulation,
-            monochromatic_color_temperature,
-            monochromatic_color_tint,
-            dynamic_range_priority,
-            dynamic_range,
-            highlight,
-            shadow,
-            color,
-            sharpness,
-            clarity,
-            noise_reduction,
-            grain,
-            color_chrome_effect,
-            color_chrome_fx_blue,
-            smooth_skin_effect,
-            white_balance,
-            white_balance_shift_red,
-            white_balance_shift_blue,
-            white_balance_temperature,
-            lens_modulation_optimizer,
-            color_space,
-        };
-
-        Ok(Box::new(sim))
+    fn default_simulation(
+        &self,
+        ptp: &mut Ptp,
+        slot: fuji::CustomSetting,
+    ) -> anyhow::Result<Box<dyn Simulation>> {
+        ptp.set_prop(DevicePropCode::FujiCustomSetting, &slot)?;
+        Ok(Box::new(read_simulation(ptp, PropSource::FactoryDefault)?))
     }
 
     fn update_simulation(
@@ -137,13 +190,9 @@ impl CameraSimulationManager for FujifilmXT5 {
 
         let mut updated_simulation = original_simulation.clone();
         simulation_modifier(&mut updated_simulation)?;
+        self.validate_simulation(&updated_simulation)?;
 
-        if let Err(error) = self.set_simulation(ptp, slot, &updated_simulation) {
-            error!("Error updating simulation options: {error}. Restoring previous options.");
-            self.set_simulation(ptp, slot, original_simulation)?;
-        }
-
-        Ok(())
+        self.set_simulation(ptp, slot, &updated_simulation)
     }
 
     fn set_simulation(
@@ -152,103 +201,197 @@ impl CameraSimulationManager for FujifilmXT5 {
         slot: fuji::CustomSetting,
         simulation: &dyn Simulation,
     ) -> anyhow::Result<()> {
+        let Some(simulation) = simulation.as_any().downcast_ref::<XT5Simulation>() else {
+            let converted = self.convert_simulation(ptp, slot, simulation)?;
+            return self.set_simulation(ptp, slot, &*converted);
+        };
+
+        self.validate_simulation(simulation)?;
+
+        self.write_simulation(ptp, slot, simulation)
+    }
+
+    fn simulation_writes(&self, simulation: &dyn Simulation) -> anyhow::Result<Vec<PropWrite>> {
         let simulation = simulation
             .as_any()
             .downcast_ref::<XT5Simulation>()
-            .expect("Simulation type mismatch");
+            .ok_or_else(|| anyhow!("Simulation was not built for the {}", FUJIFILM_X_T5.name))?;
 
-        ptp.set_prop(DevicePropCode::FujiCustomSetting, &slot)?;
-
-        ptp.set_prop(DevicePropCode::FujiCustomSettingName, &simulation.name)?;
-        ptp.set_prop(DevicePropCode::FujiCustomSettingImageSize, &simulation.size)?;
-        ptp.set_prop(
-            DevicePropCode::FujiCustomSettingImageQuality,
-            &simulation.quality,
-        )?;
-        ptp.set_prop(
-            DevicePropCode::FujiCustomSettingFilmSimulation,
-            &simulation.simulation,
-        )?;
+        let mut writes = vec![
+            PropWrite::new(DevicePropCode::FujiCustomSettingName, &simulation.name)?,
+            PropWrite::new(DevicePropCode::FujiCustomSettingImageSize, &simulation.size)?,
+            PropWrite::new(
+                DevicePropCode::FujiCustomSettingImageQuality,
+                &simulation.quality,
+            )?,
+            PropWrite::new(
+                DevicePropCode::FujiCustomSettingFilmSimulation,
+                &simulation.simulation,
+            )?,
+        ];
         if simulation.simulation.is_black_and_white() {
-            ptp.set_prop(
+            writes.push(PropWrite::new(
                 DevicePropCode::FujiCustomSettingMonochromaticColorTemperature,
                 &simulation.monochromatic_color_temperature,
-            )?;
-            ptp.set_prop(
+            )?);
+            writes.push(PropWrite::new(
                 DevicePropCode::FujiCustomSettingMonochromaticColorTint,
                 &simulation.monochromatic_color_tint,
-            )?;
+            )?);
         }
-        ptp.set_prop(
-            DevicePropCode::FujiCustomSettingDynamicRangePriority,
-            &simulation.dynamic_range_priority,
-        )?;
-        ptp.set_prop(
-            DevicePropCode::FujiCustomSettingDynamicRange,
-            &simulation.dynamic_range,
-        )?;
-        ptp.set_prop(
-            DevicePropCode::FujiCustomSettingHighlightTone,
-            &simulation.highlight,
-        )?;
-        ptp.set_prop(
-            DevicePropCode::FujiCustomSettingShadowTone,
-            &simulation.shadow,
-        )?;
-        ptp.set_prop(DevicePropCode::FujiCustomSettingColor, &simulation.color)?;
-        ptp.set_prop(
-            DevicePropCode::FujiCustomSettingSharpness,
-            &simulation.sharpness,
-        )?;
-        ptp.set_prop(
-            DevicePropCode::FujiCustomSettingClarity,
-            &simulation.clarity,
-        )?;
-        ptp.set_prop(
-            DevicePropCode::FujiCustomSettingHighISONR,
-            &simulation.noise_reduction,
-        )?;
-        ptp.set_prop(
-            DevicePropCode::FujiCustomSettingGrainEffect,
-            &simulation.grain,
-        )?;
-        ptp.set_prop(
-            DevicePropCode::FujiCustomSettingColorChromeEffect,
-            &simulation.color_chrome_effect,
-        )?;
-        ptp.set_prop(
-            DevicePropCode::FujiCustomSettingColorChromeFXBlue,
-            &simulation.color_chrome_fx_blue,
-        )?;
-        ptp.set_prop(
-            DevicePropCode::FujiCustomSettingSmoothSkinEffect,
-            &simulation.smooth_skin_effect,
-        )?;
-        ptp.set_prop(
-            DevicePropCode::FujiCustomSettingWhiteBalance,
-            &simulation.white_balance,
-        )?;
-        ptp.set_prop(
-            DevicePropCode::FujiCustomSettingWhiteBalanceShiftRed,
-            &simulation.white_balance_shift_red,
-        )?;
-        ptp.set_prop(
-            DevicePropCode::FujiCustomSettingWhiteBalanceShiftBlue,
-            &simulation.white_balance_shift_blue,
-        )?;
-        ptp.set_prop(
-            DevicePropCode::FujiCustomSettingWhiteBalanceTemperature,
-            &simulation.white_balance_temperature,
-        )?;
-        ptp.set_prop(
-            DevicePropCode::FujiCustomSettingLensModulationOptimizer,
-            &simulation.lens_modulation_optimizer,
-        )?;
-        ptp.set_prop(
-            DevicePropCode::FujiCustomSettingColorSpace,
-            &simulation.color_space,
-        )?;
-
-        Ok(())
+        writes.extend([
+            PropWrite::new(
+                DevicePropCode::FujiCustomSettingDynamicRangePriority,
+                &simulation.dynamic_range_priority,
+            )?,
+            PropWrite::new(
+                DevicePropCode::FujiCustomSettingDynamicRange,
+                &simulation.dynamic_range,
+            )?,
+            PropWrite::new(
+                DevicePropCode::FujiCustomSettingHighlightTone,
+                &simulation.highlight,
+            )?,
+            PropWrite::new(
+                DevicePropCode::FujiCustomSettingShadowTone,
+                &simulation.shadow,
+            )?,
+            PropWrite::new(DevicePropCode::FujiCustomSettingColor, &simulation.color)?,
+            PropWrite::new(
+                DevicePropCode::FujiCustomSettingSharpness,
+                &simulation.sharpness,
+            )?,
+            PropWrite::new(
+                DevicePropCode::FujiCustomSettingClarity,
+                &simulation.clarity,
+            )?,
+            PropWrite::new(
+                DevicePropCode::FujiCustomSettingHighISONR,
+                &simulation.noise_reduction,
+            )?,
+            PropWrite::new(
+                DevicePropCode::FujiCustomSettingGrainEffect,
+                &simulation.grain,
+            )?,
+            PropWrite::new(
+                DevicePropCode::FujiCustomSettingColorChromeEffect,
+                &simulation.color_chrome_effect,
+            )?,
+            PropWrite::new(
+                DevicePropCode::FujiCustomSettingColorChromeFXBlue,
+                &simulation.color_chrome_fx_blue,
+            )?,
+            PropWrite::new(
+                DevicePropCode::FujiCustomSettingSmoothSkinEffect,
+                &simulation.smooth_skin_effect,
+            )?,
+            PropWrite::new(
+                DevicePropCode::FujiCustomSettingWhiteBalance,
+                &simulation.white_balance,
+            )?,
+            PropWrite::new(
+                DevicePropCode::FujiCustomSettingWhiteBalanceShiftRed,
+                &simulation.white_balance_shift_red,
+            )?,
+            PropWrite::new(
+                DevicePropCode::FujiCustomSettingWhiteBalanceShiftBlue,
+                &simulation.white_balance_shift_blue,
+            )?,
+            PropWrite::new(
+                DevicePropCode::FujiCustomSettingWhiteBalanceTemperature,
+                &simulation.white_balance_temperature,
+            )?,
+            PropWrite::new(
+                DevicePropCode::FujiCustomSettingLensModulationOptimizer,
+                &simulation.lens_modulation_optimizer,
+            )?,
+            PropWrite::new(
+                DevicePropCode::FujiCustomSettingColorSpace,
+                &simulation.color_space,
+            )?,
+        ]);
+
+        Ok(writes)
     }
 }
+
+// Reads every property of the selected slot.
+fn read_simulation(ptp: &mut Ptp, source: PropSource) -> anyhow::Result<XT5Simulation> {
+    let name = ptp.read_prop(DevicePropCode::FujiCustomSettingName, source)?;
+    let size = ptp.read_prop(DevicePropCode::FujiCustomSettingImageSize, source)?;
+    let quality = ptp.read_prop(DevicePropCode::FujiCustomSettingImageQuality, source)?;
+    let simulation: fuji::FilmSimulation =
+        ptp.read_prop(DevicePropCode::FujiCustomSettingFilmSimulation, source)?;
+    let monochromatic_color_temperature = ptp.read_prop(
+        DevicePropCode::FujiCustomSettingMonochromaticColorTemperature,
+        source,
+    )?;
+    let monochromatic_color_tint = ptp.read_prop(
+        DevicePropCode::FujiCustomSettingMonochromaticColorTint,
+        source,
+    )?;
+    let dynamic_range_priority = ptp.read_prop(
+        DevicePropCode::FujiCustomSettingDynamicRangePriority,
+        source,
+    )?;
+    let dynamic_range = ptp.read_prop(DevicePropCode::FujiCustomSettingDynamicRange, source)?;
+    let highlight = ptp.read_prop(DevicePropCode::FujiCustomSettingHighlightTone, source)?;
+    let shadow = ptp.read_prop(DevicePropCode::FujiCustomSettingShadowTone, source)?;
+    let color = ptp.read_prop(DevicePropCode::FujiCustomSettingColor, source)?;
+    let sharpness = ptp.read_prop(DevicePropCode::FujiCustomSettingSharpness, source)?;
+    let clarity = ptp.read_prop(DevicePropCode::FujiCustomSettingClarity, source)?;
+    let noise_reduction = ptp.read_prop(DevicePropCode::FujiCustomSettingHighISONR, source)?;
+    let grain = ptp.read_prop(DevicePropCode::FujiCustomSettingGrainEffect, source)?;
+    let color_chrome_effect =
+        ptp.read_prop(DevicePropCode::FujiCustomSettingColorChromeEffect, source)?;
+    let color_chrome_fx_blue =
+        ptp.read_prop(DevicePropCode::FujiCustomSettingColorChromeFXBlue, source)?;
+    let smooth_skin_effect =
+        ptp.read_prop(DevicePropCode::FujiCustomSettingSmoothSkinEffect, source)?;
+    let white_balance =
+        ptp.read_prop::<fuji::WhiteBalance>(DevicePropCode::FujiCustomSettingWhiteBalance, source)?;
+    let white_balance_shift_red = ptp.read_prop(
+        DevicePropCode::FujiCustomSettingWhiteBalanceShiftRed,
+        source,
+    )?;
+    let white_balance_shift_blue = ptp.read_prop(
+        DevicePropCode::FujiCustomSettingWhiteBalanceShiftBlue,
+        source,
+    )?;
+    let white_balance_temperature = ptp.read_prop(
+        DevicePropCode::FujiCustomSettingWhiteBalanceTemperature,
+        source,
+    )?;
+    let lens_modulation_optimizer = ptp.read_prop(
+        DevicePropCode::FujiCustomSettingLensModulationOptimizer,
+        source,
+    )?;
+    let color_space = ptp.read_prop(DevicePropCode::FujiCustomSettingColorSpace, source)?;
+
+    Ok(XT5Simulation {
+        name,
+        size,
+        quality,
+        simulation,
+        monochromatic_color_temperature,
+        monochromatic_color_tint,
+        dynamic_range_priority,
+        dynamic_range,
+        highlight,
+        shadow,
+        color,
+        sharpness,
+        clarity,
+        noise_reduction,
+        grain,
+        color_chrome_effect,
+        color_chrome_fx_blue,
+        smooth_skin_effect,
+        white_balance,
+        white_balance_shift_red,
+        white_balance_shift_blue,
+        white_balance_temperature,
+        lens_modulation_optimizer,
+        color_space,
+    })
+}
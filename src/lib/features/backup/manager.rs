@@ -3,7 +3,7 @@ use ptp_cursor::PtpSerialize;
 
 use crate::{
     features::base::CameraBase,
-    ptp::{CommandCode, Ptp, fuji},
+    ptp::{CommandCode, ObjectInfo, Ptp, fuji, trace_layout},
 };
 
 pub const OBJECT_HANDLE: [u32; 1] = [0x0];
@@ -14,7 +14,9 @@ pub const IMPORT_OBJECT_INFO_HANDLE: [u32; 2] = [0x0, 0x0];
 pub trait CameraBackupManager: CameraBase {
     fn export_backup(&self, ptp: &mut Ptp) -> anyhow::Result<Vec<u8>> {
         debug!("Starting backup export");
-        let _ = ptp.send(CommandCode::GetObjectInfo, &EXPORT_OBJECT_INFO_HANDLE, None)?;
+        let object_info = ptp.send(CommandCode::GetObjectInfo, &EXPORT_OBJECT_INFO_HANDLE, None)?;
+        trace_layout::<ObjectInfo>("Incoming backup object info", &object_info);
+
         let response = ptp.send(CommandCode::GetObject, &OBJECT_HANDLE, None)?;
         debug!("Backup export completed");
 
@@ -23,11 +25,13 @@ pub trait CameraBackupManager: CameraBase {
 
     fn import_backup(&self, ptp: &mut Ptp, buffer: &[u8]) -> anyhow::Result<()> {
         debug!("Starting backup import");
-        let object_info = fuji::BackupObjectInfo::new(buffer.len())?;
+        let object_info = fuji::BackupObjectInfo::new(buffer.len())?.try_into_ptp()?;
+        trace_layout::<ObjectInfo>("Outgoing backup object info", &object_info);
+
         let _ = ptp.send(
             CommandCode::SendObjectInfo,
             &IMPORT_OBJECT_INFO_HANDLE,
-            Some(&object_info.try_into_ptp()?),
+            Some(&object_info),
         )?;
         let _ = ptp.send(CommandCode::SendObject, &OBJECT_HANDLE, Some(buffer))?;
         debug!("Backup import completed");
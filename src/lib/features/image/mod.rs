@@ -7,9 +7,13 @@ use std::{
 use anyhow::bail;
 use byteorder::{LittleEndian, ReadBytesExt};
 use exiftool::ExifTool;
-use log::debug;
+use log::{debug, warn};
 
-use crate::features::simulation::Simulation;
+use crate::{
+    SUPPORTED,
+    features::simulation::{Recipe, Simulation, SupportedValues},
+    ptp::fuji,
+};
 
 pub const MAKER_NOTES_TAG: &str = "MakerNotes";
 
@@ -19,7 +23,45 @@ pub fn extract_simulation(image: &Path) -> anyhow::Result<Box<dyn Simulation>> {
     let maker_notes = exiftool.read_tag_binary(image, MAKER_NOTES_TAG)?;
     debug!("{:x?}", maker_notes);
 
-    let mut cursor = Cursor::new(&maker_notes);
+    let fields = parse_maker_notes(&maker_notes)?;
+    debug!("{:x?}", fields);
+
+    let model: Option<String> = exiftool.read_tag(image, "Model", &[])?;
+    let camera = camera_values(model.as_deref());
+
+    let mut recipe = MakerNotes(fields).to_recipe();
+    recipe.size = read_size(&mut exiftool, image, camera.map(|(_, values)| values))?;
+    recipe.color_space = read_color_space(&mut exiftool, image)?;
+
+    let missing: Vec<&str> = recipe
+        .fields()
+        .into_iter()
+        .filter(|(label, value)| value.is_none() && is_applicable(&recipe, label))
+        .map(|(label, _)| label)
+        .collect();
+    if !missing.is_empty() {
+        warn!(
+            "Could not recover {} from {}",
+            missing.join(", "),
+            image.display()
+        );
+    }
+
+    let Some((name, values)) = camera else {
+        debug!("Unknown model {model:?}, keeping the recipe as is");
+        return Ok(Box::new(recipe));
+    };
+
+    // Fits the recipe to the values the camera that took the image supports.
+    let mut adapted = Recipe::default();
+    recipe.apply_to_simulation(&mut adapted, name, values)?;
+    Ok(Box::new(adapted))
+}
+
+fn parse_maker_notes(
+    maker_notes: &[u8],
+) -> anyhow::Result<HashMap<FujiExifMakerNoteTag, MakerNoteEntry<'_>>> {
+    let mut cursor = Cursor::new(maker_notes);
 
     let mut header = [0u8; 8];
     cursor.read_exact(&mut header)?;
@@ -38,8 +80,7 @@ pub fn extract_simulation(image: &Path) -> anyhow::Result<Box<dyn Simulation>> {
     let entries_len = cursor.read_u16::<LittleEndian>()?;
     debug!("Entries: {:?}", entries_len);
 
-    let mut fields: HashMap<FujiExifMakerNoteTag, &[u8]> =
-        HashMap::with_capacity(entries_len as usize);
+    let mut fields = HashMap::with_capacity(entries_len as usize);
 
     for _ in 0..entries_len {
         let tag = FujiExifMakerNoteTag::try_from(cursor.read_u16::<LittleEndian>()?);
@@ -58,36 +99,498 @@ pub fn extract_simulation(image: &Path) -> anyhow::Result<Box<dyn Simulation>> {
 
         let value_len = field_type.size() * count as usize;
 
-        let value: &[u8] = if value_len <= 4 {
-            let start = (cursor.position() - 4).try_into()?;
-            &maker_notes[start..start + value_len]
+        let start: usize = if value_len <= 4 {
+            (cursor.position() - 4).try_into()?
         } else {
-            let start = raw.try_into()?;
-            &maker_notes[start..start + value_len]
+            raw.try_into()?
+        };
+        let Some(value) = maker_notes.get(start..start + value_len) else {
+            warn!("MakerNotes entry {tag:?} is out of bounds, skipping it");
+            continue;
         };
 
-        fields.insert(tag, value);
+        fields.insert(tag, MakerNoteEntry { field_type, value });
     }
 
-    debug!("{:x?}", fields);
+    Ok(fields)
+}
 
-    todo!()
+#[derive(Debug, Clone, Copy)]
+struct MakerNoteEntry<'a> {
+    field_type: IFDType,
+    value: &'a [u8],
 }
 
+impl MakerNoteEntry<'_> {
+    fn ints(&self) -> Vec<i64> {
+        self.value
+            .chunks_exact(self.field_type.size())
+            .filter_map(|chunk| match self.field_type {
+                IFDType::Byte | IFDType::Undefined => Some(i64::from(chunk[0])),
+                IFDType::SByte => Some(i64::from(i8::from_le_bytes([chunk[0]]))),
+                IFDType::Short => Some(i64::from(u16::from_le_bytes(chunk.try_into().ok()?))),
+                IFDType::SShort => Some(i64::from(i16::from_le_bytes(chunk.try_into().ok()?))),
+                IFDType::Long => Some(i64::from(u32::from_le_bytes(chunk.try_into().ok()?))),
+                IFDType::SLong => Some(i64::from(i32::from_le_bytes(chunk.try_into().ok()?))),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn text(&self) -> String {
+        String::from_utf8_lossy(self.value)
+            .trim_end_matches('\0')
+            .trim()
+            .to_string()
+    }
+}
+
+struct MakerNotes<'a>(HashMap<FujiExifMakerNoteTag, MakerNoteEntry<'a>>);
+
+impl MakerNotes<'_> {
+    fn int(&self, tag: FujiExifMakerNoteTag) -> Option<i64> {
+        self.0.get(&tag)?.ints().first().copied()
+    }
+
+    // Looks up a tag's value in `table`, warning about values it does not know.
+    fn lookup<T: Copy>(
+        &self,
+        tag: FujiExifMakerNoteTag,
+        label: &str,
+        table: &[(i64, T)],
+    ) -> Option<T> {
+        let value = self.int(tag)?;
+        let found = table
+            .iter()
+            .find(|(raw, _)| *raw == value)
+            .map(|(_, mapped)| *mapped);
+        if found.is_none() {
+            warn!("Unknown {label} value {value:#x} in MakerNotes, skipping it");
+        }
+        found
+    }
+
+    // Converts a numeric tag, warning if the camera recorded something out of range.
+    fn convert<T>(
+        &self,
+        tag: FujiExifMakerNoteTag,
+        label: &str,
+        convert: impl Fn(i64) -> anyhow::Result<T>,
+    ) -> Option<T> {
+        let value = self.int(tag)?;
+        convert(value)
+            .inspect_err(|e| warn!("Could not read {label} from MakerNotes: {e}"))
+            .ok()
+    }
+
+    fn quality(&self) -> Option<fuji::ImageQuality> {
+        let quality = self.0.get(&FujiExifMakerNoteTag::ImageQuality)?.text();
+        match quality.to_uppercase().as_str() {
+            "FINE" => Some(fuji::ImageQuality::Fine),
+            "NORMAL" => Some(fuji::ImageQuality::Normal),
+            "RAW" => Some(fuji::ImageQuality::Raw),
+            _ => {
+                warn!("Unknown Quality '{quality}' in MakerNotes, skipping it");
+                None
+            }
+        }
+    }
+
+    fn to_recipe(&self) -> Recipe {
+        use FujiExifMakerNoteTag as Tag;
+
+        let mut recipe = Recipe {
+            quality: self.quality(),
+            ..Default::default()
+        };
+
+        // NOTE: Saturation holds Color for color film simulations, and the film simulation itself
+        // for monochrome ones, where FilmMode is not recorded.
+        let saturation = self.lookup(Tag::Saturation, "Saturation", SATURATION);
+        recipe.simulation = match saturation {
+            Some(Saturation::Simulation(simulation)) => Some(simulation),
+            _ => self.lookup(Tag::FilmMode, "Film Mode", FILM_MODES),
+        };
+        if let Some(Saturation::Color(color)) = saturation {
+            recipe.color = fuji::Color::try_from_int(color).ok();
+        }
+
+        if recipe
+            .simulation
+            .is_some_and(fuji::FilmSimulation::is_black_and_white)
+        {
+            recipe.monochromatic_color_temperature = self.convert(
+                Tag::MonochromaticColorTemperature,
+                "Monochromatic Color Temperature",
+                |value| fuji::MonochromaticColorShift::try_from_int(i16::try_from(value)?),
+            );
+            recipe.monochromatic_color_tint = self.convert(
+                Tag::MonochromaticColorTint,
+                "Monochromatic Color Tint",
+                |value| fuji::MonochromaticColorShift::try_from_int(i16::try_from(value)?),
+            );
+        }
+
+        recipe.highlight = self.convert(Tag::Highlight, "Highlight", |value| {
+            fuji::HighlightTone::try_from_float(tone(value)?)
+        });
+        recipe.shadow = self.convert(Tag::Shadow, "Shadow", |value| {
+            fuji::ShadowTone::try_from_float(tone(value)?)
+        });
+
+        recipe.sharpness = self
+            .lookup(Tag::Sharpness, "Sharpness", SHARPNESS)
+            .flatten()
+            .and_then(|value| fuji::Sharpness::try_from_int(value).ok());
+
+        recipe.clarity = self.convert(Tag::Clarity, "Clarity", |value| {
+            if value % 1000 != 0 {
+                bail!("Value {value} is not a whole step");
+            }
+            fuji::Clarity::try_from_int(i16::try_from(value / 1000)?)
+        });
+
+        recipe.noise_reduction = if self.0.contains_key(&Tag::NoiseReduction2) {
+            self.lookup(Tag::NoiseReduction2, "Noise Reduction", NOISE_REDUCTION)
+        } else {
+            if self.int(Tag::NoiseReduction1).is_some() {
+                warn!("Noise Reduction is recorded in an older format that cannot be mapped");
+            }
+            None
+        };
+
+        let roughness = self.lookup(Tag::GrainEffectRoughness, "Grain Roughness", STRENGTHS);
+        // Bodies that record no grain size only have the small grain.
+        let size = match self.int(Tag::GrainEffectSize) {
+            Some(_) => self.lookup(Tag::GrainEffectSize, "Grain Size", GRAIN_SIZES),
+            None => Some(Some(false)),
+        };
+        recipe.grain = match (roughness, size) {
+            (Some(Strength::Off), _) | (_, Some(None)) => Some(fuji::GrainEffect::Off),
+            (Some(Strength::Weak), Some(Some(true))) => Some(fuji::GrainEffect::WeakLarge),
+            (Some(Strength::Weak), Some(Some(false))) => Some(fuji::GrainEffect::WeakSmall),
+            (Some(Strength::Strong), Some(Some(true))) => Some(fuji::GrainEffect::StrongLarge),
+            (Some(Strength::Strong), Some(Some(false))) => Some(fuji::GrainEffect::StrongSmall),
+            _ => None,
+        };
+
+        recipe.color_chrome_effect = self
+            .lookup(Tag::ColorChromeEffect, "Color Chrome Effect", STRENGTHS)
+            .map(|strength| match strength {
+                Strength::Strong => fuji::ColorChromeEffect::Strong,
+                Strength::Weak => fuji::ColorChromeEffect::Weak,
+                Strength::Off => fuji::ColorChromeEffect::Off,
+            });
+        recipe.color_chrome_fx_blue = self
+            .lookup(Tag::ColorChromeFXBlue, "Color Chrome FX Blue", STRENGTHS)
+            .map(|strength| match strength {
+                Strength::Strong => fuji::ColorChromeFXBlue::Strong,
+                Strength::Weak => fuji::ColorChromeFXBlue::Weak,
+                Strength::Off => fuji::ColorChromeFXBlue::Off,
+            });
+        recipe.smooth_skin_effect = self
+            .lookup(Tag::SmoothSkinEffect, "Smooth Skin Effect", STRENGTHS)
+            .map(|strength| match strength {
+                Strength::Strong => fuji::SmoothSkinEffect::Strong,
+                Strength::Weak => fuji::SmoothSkinEffect::Weak,
+                Strength::Off => fuji::SmoothSkinEffect::Off,
+            });
+
+        recipe.white_balance = self.lookup(Tag::WhiteBalance, "White Balance", WHITE_BALANCES);
+        if recipe.white_balance == Some(fuji::WhiteBalance::Temperature) {
+            recipe.white_balance_temperature = self.convert(
+                Tag::ColorTemperature,
+                "White Balance Temperature",
+                |value| fuji::WhiteBalanceTemperature::try_from_int(i16::try_from(value)?),
+            );
+        }
+
+        if let Some(entry) = self.0.get(&Tag::WhiteBalanceFineTune) {
+            match entry.ints().as_slice() {
+                [red, blue] => {
+                    recipe.white_balance_shift_red = white_balance_shift(*red, "Red");
+                    recipe.white_balance_shift_blue = white_balance_shift(*blue, "Blue");
+                }
+                values => warn!("Unexpected White Balance Fine Tune {values:?} in MakerNotes"),
+            }
+        }
+
+        recipe.dynamic_range = match self.int(Tag::DynamicRangeSetting) {
+            Some(0) => Some(fuji::DynamicRange::Auto),
+            _ => self.lookup(
+                Tag::DevelopmentDynamicRange,
+                "Dynamic Range",
+                DYNAMIC_RANGES,
+            ),
+        };
+
+        recipe.dynamic_range_priority = match self.int(Tag::DynamicRangePriority) {
+            Some(0) => Some(fuji::DynamicRangePriority::Auto),
+            Some(1) => self.lookup(
+                Tag::DynamicRangePriorityFixed,
+                "Dynamic Range Priority",
+                DYNAMIC_RANGE_PRIORITIES,
+            ),
+            Some(value) => {
+                warn!("Unknown Dynamic Range Priority value {value:#x} in MakerNotes, skipping it");
+                None
+            }
+            None => None,
+        };
+
+        recipe.lens_modulation_optimizer = self
+            .lookup(
+                Tag::LensModulationOptimizer,
+                "Lens Modulation Optimizer",
+                &[(0, false), (1, true)],
+            )
+            .map(fuji::LensModulationOptimizer::from_bool);
+
+        recipe
+    }
+}
+
+// Highlight and Shadow are recorded in sixteenths, with the sign flipped.
+#[allow(clippy::cast_precision_loss)]
+fn tone(value: i64) -> anyhow::Result<f32> {
+    if value % 8 != 0 {
+        bail!("Value {value} is not a half step");
+    }
+    Ok(-(value as f32) / 16.0)
+}
+
+// Newer bodies record shifts in twentieths, older ones record the shift itself.
+fn white_balance_shift(value: i64, channel: &str) -> Option<fuji::WhiteBalanceShift> {
+    let shift = if (-9..=9).contains(&value) {
+        Some(value)
+    } else if value % 20 == 0 {
+        Some(value / 20)
+    } else {
+        None
+    };
+
+    let shift = shift
+        .and_then(|shift| i16::try_from(shift).ok())
+        .and_then(|shift| fuji::WhiteBalanceShift::try_from_int(shift).ok());
+    if shift.is_none() {
+        warn!("Unknown White Balance Shift {channel} value {value} in MakerNotes, skipping it");
+    }
+    shift
+}
+
+// Whether a field missing from the recipe is worth reporting, given the rest of it.
+fn is_applicable(recipe: &Recipe, label: &str) -> bool {
+    match label {
+        "Name" => false,
+        "Monochromatic Color Temperature" | "Monochromatic Color Tint" => recipe
+            .simulation
+            .is_some_and(fuji::FilmSimulation::is_black_and_white),
+        "Color" => !recipe
+            .simulation
+            .is_some_and(fuji::FilmSimulation::is_black_and_white),
+        "White Balance Temperature" => {
+            recipe.white_balance == Some(fuji::WhiteBalance::Temperature)
+        }
+        _ => true,
+    }
+}
+
+// Pixel dimensions depend on the sensor, so they can only be mapped to a size code for a known model.
+fn read_size(
+    exiftool: &mut ExifTool,
+    image: &Path,
+    values: Option<&SupportedValues>,
+) -> anyhow::Result<Option<fuji::ImageSize>> {
+    let width: Option<u32> = exiftool.read_tag(image, "ImageWidth", &[])?;
+    let height: Option<u32> = exiftool.read_tag(image, "ImageHeight", &[])?;
+    let (Some(width), Some(height), Some(values)) = (width, height, values) else {
+        return Ok(None);
+    };
+
+    // Portrait images are stored rotated, but sizes are always given in landscape.
+    let (width, height) = (width.max(height), width.min(height));
+    Ok(values.image_size_from_dimensions(width, height))
+}
+
+fn read_color_space(
+    exiftool: &mut ExifTool,
+    image: &Path,
+) -> anyhow::Result<Option<fuji::ColorSpace>> {
+    let color_space: Option<String> = exiftool.read_tag(image, "ColorSpace", &[])?;
+    let Some(color_space) = color_space else {
+        return Ok(None);
+    };
+
+    if color_space == "sRGB" {
+        return Ok(Some(fuji::ColorSpace::SRGB));
+    }
+
+    // NOTE: Adobe RGB images are marked uncalibrated, with the R03 interoperability index.
+    let interop: Option<String> = exiftool.read_tag(image, "InteropIndex", &[])?;
+    if interop.is_some_and(|interop| interop.starts_with("R03")) {
+        return Ok(Some(fuji::ColorSpace::AdobeRGB));
+    }
+
+    Ok(None)
+}
+
+fn camera_values(model: Option<&str>) -> Option<(&'static str, &'static SupportedValues)> {
+    let model = model?.trim();
+    let camera = SUPPORTED
+        .iter()
+        .find(|camera| camera.name.strip_prefix("FUJIFILM ") == Some(model))?;
+
+    let base = (camera.camera_factory)();
+    let parser = base.as_simulation_parser()?;
+    Some((camera.name, parser.supported_values()))
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Saturation {
+    // Left to the film simulation, which has no equivalent setting.
+    Unset,
+    Color(i16),
+    Simulation(fuji::FilmSimulation),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Strength {
+    Strong,
+    Weak,
+    Off,
+}
+
+const SATURATION: &[(i64, Saturation)] = &[
+    (0x8000, Saturation::Unset),
+    (0x0, Saturation::Color(0)),
+    (0x80, Saturation::Color(1)),
+    (0x100, Saturation::Color(2)),
+    (0xc0, Saturation::Color(3)),
+    (0xe0, Saturation::Color(4)),
+    (0x180, Saturation::Color(-1)),
+    (0x400, Saturation::Color(-2)),
+    (0x4c0, Saturation::Color(-3)),
+    (0x4e0, Saturation::Color(-4)),
+    (
+        0x300,
+        Saturation::Simulation(fuji::FilmSimulation::Monochrome),
+    ),
+    (
+        0x301,
+        Saturation::Simulation(fuji::FilmSimulation::MonochromeR),
+    ),
+    (
+        0x302,
+        Saturation::Simulation(fuji::FilmSimulation::MonochromeYe),
+    ),
+    (
+        0x303,
+        Saturation::Simulation(fuji::FilmSimulation::MonochromeG),
+    ),
+    (0x310, Saturation::Simulation(fuji::FilmSimulation::Sepia)),
+    (
+        0x500,
+        Saturation::Simulation(fuji::FilmSimulation::AcrosSTD),
+    ),
+    (0x501, Saturation::Simulation(fuji::FilmSimulation::AcrosR)),
+    (0x502, Saturation::Simulation(fuji::FilmSimulation::AcrosYe)),
+    (0x503, Saturation::Simulation(fuji::FilmSimulation::AcrosG)),
+];
+
+const FILM_MODES: &[(i64, fuji::FilmSimulation)] = &[
+    (0x0, fuji::FilmSimulation::Provia),
+    (0x120, fuji::FilmSimulation::Astia),
+    (0x200, fuji::FilmSimulation::Velvia),
+    (0x400, fuji::FilmSimulation::Velvia),
+    (0x500, fuji::FilmSimulation::PRONegStd),
+    (0x501, fuji::FilmSimulation::PRONegHi),
+    (0x600, fuji::FilmSimulation::ClassicChrome),
+    (0x700, fuji::FilmSimulation::Eterna),
+    (0x800, fuji::FilmSimulation::ClassicNegative),
+    (0x900, fuji::FilmSimulation::EternaBleachBypass),
+    (0xa00, fuji::FilmSimulation::NostalgicNegative),
+    (0xb00, fuji::FilmSimulation::RealaAce),
+];
+
+// NOTE: 0x8000 means the film simulation decides, which has no equivalent setting.
+const SHARPNESS: &[(i64, Option<i16>)] = &[
+    (0x0, Some(-4)),
+    (0x1, Some(-3)),
+    (0x2, Some(-2)),
+    (0x82, Some(-1)),
+    (0x3, Some(0)),
+    (0x84, Some(1)),
+    (0x4, Some(2)),
+    (0x5, Some(3)),
+    (0x6, Some(4)),
+    (0x8000, None),
+];
+
+const NOISE_REDUCTION: &[(i64, fuji::NoiseReduction)] = &[
+    (0x1e0, fuji::NoiseReduction::Plus4),
+    (0x1c0, fuji::NoiseReduction::Plus3),
+    (0x100, fuji::NoiseReduction::Plus2),
+    (0x180, fuji::NoiseReduction::Plus1),
+    (0x0, fuji::NoiseReduction::Zero),
+    (0x280, fuji::NoiseReduction::Minus1),
+    (0x200, fuji::NoiseReduction::Minus2),
+    (0x2c0, fuji::NoiseReduction::Minus3),
+    (0x2e0, fuji::NoiseReduction::Minus4),
+];
+
+const STRENGTHS: &[(i64, Strength)] = &[
+    (0, Strength::Off),
+    (32, Strength::Weak),
+    (64, Strength::Strong),
+];
+
+// Whether the grain is large, or None when grain is off.
+const GRAIN_SIZES: &[(i64, Option<bool>)] = &[(0, None), (16, Some(false)), (32, Some(true))];
+
+const WHITE_BALANCES: &[(i64, fuji::WhiteBalance)] = &[
+    (0x0, fuji::WhiteBalance::Auto),
+    (0x1, fuji::WhiteBalance::WhitePriority),
+    (0x2, fuji::WhiteBalance::AmbiencePriority),
+    (0x100, fuji::WhiteBalance::Daylight),
+    (0x200, fuji::WhiteBalance::Shade),
+    (0x300, fuji::WhiteBalance::Fluorescent1),
+    (0x301, fuji::WhiteBalance::Fluorescent2),
+    (0x302, fuji::WhiteBalance::Fluorescent3),
+    (0x400, fuji::WhiteBalance::Incandescent),
+    (0x600, fuji::WhiteBalance::Underwater),
+    (0xf00, fuji::WhiteBalance::Custom1),
+    (0xf01, fuji::WhiteBalance::Custom2),
+    (0xf02, fuji::WhiteBalance::Custom3),
+    (0xff0, fuji::WhiteBalance::Temperature),
+];
+
+const DYNAMIC_RANGES: &[(i64, fuji::DynamicRange)] = &[
+    (100, fuji::DynamicRange::HDR100),
+    (200, fuji::DynamicRange::HDR200),
+    (400, fuji::DynamicRange::HDR400),
+    (800, fuji::DynamicRange::HDR800),
+];
+
+const DYNAMIC_RANGE_PRIORITIES: &[(i64, fuji::DynamicRangePriority)] = &[
+    (1, fuji::DynamicRangePriority::Weak),
+    (2, fuji::DynamicRangePriority::Strong),
+];
+
 #[repr(u16)]
 #[derive(
     Debug, Clone, Copy, PartialEq, Eq, Hash, num_enum::IntoPrimitive, num_enum::TryFromPrimitive,
 )]
 pub enum FujiExifMakerNoteTag {
-    // ImageSize, Get from metadata
+    // ImageSize and ColorSpace are read from the standard EXIF tags
     ImageQuality = 0x1000,
     Sharpness = 0x1001,
     WhiteBalance = 0x1002,
-    Saturation = 0x1003, // Both Color and monochrome/sepia film simulations, motherfucker
+    Saturation = 0x1003, // Both Color and monochrome/sepia film simulations
     Contrast1 = 0x1004,  // What
     ColorTemperature = 0x1005,
-    Contrast2 = 0x1006,       // What
-    NoiseReduction1 = 0x100b, // for older cameras maybe?
+    Contrast2 = 0x1006,            // What
+    WhiteBalanceFineTune = 0x100a, // Red and blue shifts
+    NoiseReduction1 = 0x100b,      // for older cameras maybe?
     NoiseReduction2 = 0x100e,
     Clarity = 0x100f,
     Shadow = 0x1040,
@@ -100,13 +603,12 @@ pub enum FujiExifMakerNoteTag {
     GrainEffectSize = 0x104c,
     ColorChromeFXBlue = 0x104e,
     FilmMode = 0x1401,                // Film Simulation
+    DynamicRangeSetting = 0x1402,     // Auto or manual
     DevelopmentDynamicRange = 0x1403, // This is the one we want for XT-5
     SmoothSkinEffect = 0x104a,
-    WhiteBalanceShiftRed = 0x144a,
-    WhiteBalanceShiftGreen = 0x144b,
-    WhiteBalanceShiftBlue = 0x144c,
-    DynamicRangePriority = 0x1444, // same bollocks with HDR800+ as PTP
-    ColorSpace = 0xa001,
+    DynamicRangePriority = 0x1443,      // Auto or fixed
+    DynamicRangePriorityAuto = 0x1444,  // What the camera picked when auto
+    DynamicRangePriorityFixed = 0x1445, // same bollocks with HDR800+ as PTP
 }
 
 #[derive(
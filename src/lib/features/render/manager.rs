@@ -1,13 +1,15 @@
 use std::{thread::sleep, time::Duration};
 
-use log::debug;
-use ptp_cursor::{PtpDeserialize, PtpSerialize};
+use log::{debug, info, warn};
+use ptp_cursor::{PtpArray, PtpDeserializeBorrowed, PtpSerialize};
 
 use crate::{
     features::{
-        base::CameraBase, render::ConversionProfile, simulation::parser::CameraSimulationParser,
+        base::CameraBase,
+        render::{ConversionProfile, RequestedProfile},
+        simulation::{Recipe, parser::CameraSimulationParser, rules},
     },
-    ptp::{CommandCode, DevicePropCode, ObjectFormat, ObjectInfo, Ptp},
+    ptp::{CommandCode, DevicePropCode, ObjectFormat, ObjectInfo, Ptp, trace_layout},
 };
 
 pub const OUTGOING_OBJECT_HANDLE: [u32; 3] = [0x0, 0x0, 0x0];
@@ -15,6 +17,32 @@ pub const INCOMING_OBJECT_HANDLE: [u32; 3] = [u32::MAX, 0x0, 0x0];
 
 // NOTE: Naively assuming that all cameras render in a similar way
 pub trait CameraRenderManager: CameraBase + CameraSimulationParser {
+    // A dry run does not send the image, so only the requested settings can be checked and shown.
+    fn dry_run_render(
+        &self,
+        conversion_profile_modifier: &mut dyn FnMut(
+            &mut dyn ConversionProfile,
+        ) -> anyhow::Result<()>,
+    ) -> anyhow::Result<Vec<u8>> {
+        let model = self.camera_definition().name;
+        warn!("Dry run: the image is not sent, so settings cannot be checked against its own");
+
+        let mut requested = RequestedProfile::new(model, self.supported_values());
+        conversion_profile_modifier(&mut requested)?;
+        rules::enforce(&rules::check_changes(
+            &Recipe::default(),
+            &requested.recipe,
+            model,
+            self.supported_values(),
+        ))?;
+
+        for (label, value) in requested.settings() {
+            info!("Dry run: would set {label} = {value}");
+        }
+
+        Ok(Vec::new())
+    }
+
     fn send_image(&self, ptp: &mut Ptp, image: &[u8]) -> anyhow::Result<()> {
         debug!("Sending image to camera");
         let object_info = ObjectInfo {
@@ -24,10 +52,13 @@ pub trait CameraRenderManager: CameraBase + CameraSimulationParser {
             ..Default::default()
         };
 
+        let object_info = object_info.try_into_ptp()?;
+        trace_layout::<ObjectInfo>("Outgoing object info", &object_info);
+
         ptp.send(
             CommandCode::FujiSendObjectInfo,
             &OUTGOING_OBJECT_HANDLE,
-            Some(&object_info.try_into_ptp()?),
+            Some(&object_info),
         )?;
         ptp.send(CommandCode::FujiSendObject, &[], Some(image))?;
         debug!("Sent image to camera");
@@ -39,13 +70,18 @@ pub trait CameraRenderManager: CameraBase + CameraSimulationParser {
         debug!("Starting image render");
         ptp.set_prop(DevicePropCode::FujiRawConversionRun, &u16::from(!draft))?;
 
+        if ptp.dry_run {
+            return Ok(Vec::new());
+        }
+
         let handle;
         loop {
             debug!("Fetching rendered object handles");
             let response = ptp.send(CommandCode::GetObjectHandles, &[u32::MAX, 0, 0], None)?;
-            let response = <Vec<u32>>::try_from_ptp(&response)?;
-            if !response.is_empty() {
-                handle = response[0];
+            let handles =
+                PtpArray::<u32>::try_from_ptp_borrowed_with_limits(&response, ptp.limits)?;
+            if let Some(first) = handles.first() {
+                handle = first;
                 break;
             }
 
@@ -3,15 +3,19 @@ pub mod manager;
 pub use manager::{CameraRenderManager, INCOMING_OBJECT_HANDLE, OUTGOING_OBJECT_HANDLE};
 
 use erased_serde::serialize_trait_object;
+use serde::Serialize;
 
-use crate::{features::simulation::Simulation, ptp::fuji};
+use crate::{
+    features::simulation::{Recipe, Simulation, SupportedValues},
+    ptp::fuji,
+};
 
 macro_rules! setter {
     ($name:ident, $type:ty) => {
         fn $name(&mut self, _value: &$type) -> anyhow::Result<()> {
             anyhow::bail!(
                 "This conversion profile does not support setting {}",
-                stringify!($ty)
+                stringify!($type)
             );
         }
     };
@@ -53,3 +57,107 @@ pub trait ConversionProfile: erased_serde::Serialize {
 }
 
 serialize_trait_object!(ConversionProfile);
+
+macro_rules! record {
+    ($name:ident, $type:ty) => {
+        fn $name(&mut self, value: &$type) -> anyhow::Result<()> {
+            self.recipe.$name(value)
+        }
+    };
+}
+
+// The settings a render was asked to apply. Dry runs never send the image, so the camera cannot
+// provide the conversion profile these settings would be applied to.
+#[derive(Serialize)]
+pub struct RequestedProfile {
+    #[serde(skip)]
+    model: &'static str,
+    #[serde(skip)]
+    values: &'static SupportedValues,
+    pub recipe: Recipe,
+    pub file_type: Option<fuji::FileType>,
+    pub exposure_offset: Option<fuji::ExposureOffset>,
+    pub teleconverter: Option<fuji::Teleconverter>,
+}
+
+impl RequestedProfile {
+    pub fn new(model: &'static str, values: &'static SupportedValues) -> Self {
+        Self {
+            model,
+            values,
+            recipe: Recipe::default(),
+            file_type: None,
+            exposure_offset: None,
+            teleconverter: None,
+        }
+    }
+
+    // Each requested setting by label, formatted the way property writes are displayed.
+    pub fn settings(&self) -> Vec<(&'static str, String)> {
+        let mut settings: Vec<(&'static str, String)> = self
+            .recipe
+            .fields()
+            .into_iter()
+            .filter_map(|(label, value)| Some((label, value?)))
+            .collect();
+        settings.extend(self.file_type.map(|value| ("File Type", value.to_string())));
+        settings.extend(
+            self.exposure_offset
+                .map(|value| ("Exposure Offset", value.to_string())),
+        );
+        settings.extend(
+            self.teleconverter
+                .map(|value| ("Teleconverter", value.to_string())),
+        );
+        settings
+    }
+}
+
+impl ConversionProfile for RequestedProfile {
+    fn set_from_simulation(&mut self, simulation: &dyn Simulation) -> anyhow::Result<()> {
+        let (model, values) = (self.model, self.values);
+        simulation.to_recipe().apply_to_profile(self, model, values)
+    }
+
+    fn set_file_type(&mut self, value: &fuji::FileType) -> anyhow::Result<()> {
+        self.file_type = Some(*value);
+        Ok(())
+    }
+
+    fn set_exposure_offset(&mut self, value: &fuji::ExposureOffset) -> anyhow::Result<()> {
+        self.exposure_offset = Some(*value);
+        Ok(())
+    }
+
+    fn set_teleconverter(&mut self, value: &fuji::Teleconverter) -> anyhow::Result<()> {
+        self.teleconverter = Some(*value);
+        Ok(())
+    }
+
+    record!(set_size, fuji::ImageSize);
+    record!(set_quality, fuji::ImageQuality);
+    record!(set_simulation, fuji::FilmSimulation);
+    record!(
+        set_monochromatic_color_temperature,
+        fuji::MonochromaticColorShift
+    );
+    record!(set_monochromatic_color_tint, fuji::MonochromaticColorShift);
+    record!(set_highlight, fuji::HighlightTone);
+    record!(set_shadow, fuji::ShadowTone);
+    record!(set_color, fuji::Color);
+    record!(set_sharpness, fuji::Sharpness);
+    record!(set_clarity, fuji::Clarity);
+    record!(set_noise_reduction, fuji::NoiseReduction);
+    record!(set_grain, fuji::GrainEffect);
+    record!(set_color_chrome_effect, fuji::ColorChromeEffect);
+    record!(set_color_chrome_fx_blue, fuji::ColorChromeFXBlue);
+    record!(set_smooth_skin_effect, fuji::SmoothSkinEffect);
+    record!(set_white_balance, fuji::WhiteBalance);
+    record!(set_white_balance_shift_red, fuji::WhiteBalanceShift);
+    record!(set_white_balance_shift_blue, fuji::WhiteBalanceShift);
+    record!(set_white_balance_temperature, fuji::WhiteBalanceTemperature);
+    record!(set_dynamic_range, fuji::DynamicRange);
+    record!(set_dynamic_range_priority, fuji::DynamicRangePriority);
+    record!(set_lens_modulation_optimizer, fuji::LensModulationOptimizer);
+    record!(set_color_space, fuji::ColorSpace);
+}
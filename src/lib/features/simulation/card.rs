@@ -0,0 +1,357 @@
+use std::{fmt::Write, str::FromStr};
+
+use anyhow::{Context, bail};
+use log::{debug, warn};
+
+use crate::{
+    features::simulation::Recipe,
+    input::{Choices, CleanAlphanumeric},
+    ptp::fuji,
+};
+
+// Parses recipe cards as published on film recipe sites, e.g.
+// "Film Simulation: Classic Chrome / Grain Effect: Weak, Small / DR400 / Highlight: -1".
+pub fn parse_card(text: &str) -> anyhow::Result<Recipe> {
+    let mut recipe = Recipe::default();
+
+    for entry in text
+        .lines()
+        .flat_map(|line| line.split(" / "))
+        .map(|entry| entry.trim().trim_start_matches(['-', '*', '#']).trim())
+        .filter(|entry| !entry.is_empty())
+    {
+        parse_entry(&mut recipe, entry)
+            .with_context(|| format!("Invalid recipe line '{entry}'"))?;
+    }
+
+    if recipe == Recipe::default() {
+        bail!("No recipe settings found");
+    }
+
+    Ok(recipe)
+}
+
+fn parse_entry(recipe: &mut Recipe, entry: &str) -> anyhow::Result<()> {
+    let Some((key, value)) = entry.split_once(':') else {
+        // Bare values such as "DR400" are common in one-line cards.
+        let clean = entry.clean();
+        if clean.starts_with("dr") || clean.starts_with("hdr") {
+            recipe.dynamic_range = Some(parse_dynamic_range(entry)?);
+        } else {
+            debug!("Ignoring recipe line '{entry}'");
+        }
+        return Ok(());
+    };
+
+    let value = value.trim();
+
+    match key.clean().replace('-', "").as_str() {
+        "name" | "title" | "recipe" => recipe.name = Some(value.parse()?),
+        "filmsimulation" | "simulation" | "film" | "filmsim" => {
+            recipe.simulation = Some(parse_fuzzy("Film Simulation", value)?);
+        }
+        "imagesize" | "size" => {
+            recipe.size = Some(fuji::CanonicalImageSize::from_str(value)?.into())
+        }
+        "imagequality" | "quality" => recipe.quality = Some(parse_fuzzy("Quality", value)?),
+        "graineffect" | "grain" => recipe.grain = Some(parse_fuzzy("Grain Effect", value)?),
+        "colorchromeeffect" | "colourchromeeffect" | "cce" => {
+            recipe.color_chrome_effect = Some(parse_fuzzy("Color Chrome Effect", value)?);
+        }
+        "colorchromefxblue"
+        | "colourchromefxblue"
+        | "colorchromeeffectblue"
+        | "fxblue"
+        | "ccfxb" => {
+            recipe.color_chrome_fx_blue = Some(parse_fuzzy("Color Chrome FX Blue", value)?);
+        }
+        "smoothskineffect" | "smoothskin" => {
+            recipe.smooth_skin_effect = Some(parse_fuzzy("Smooth Skin Effect", value)?);
+        }
+        "whitebalance" | "wb" => parse_white_balance(recipe, value)?,
+        "dynamicrange" | "drange" | "dr" => {
+            recipe.dynamic_range = Some(parse_dynamic_range(value)?)
+        }
+        "dynamicrangepriority" | "drangepriority" | "drp" => {
+            recipe.dynamic_range_priority = Some(parse_fuzzy("Dynamic Range Priority", value)?);
+        }
+        "highlight" | "highlights" | "highlighttone" => {
+            recipe.highlight = Some(first_token(value).parse()?)
+        }
+        "shadow" | "shadows" | "shadowtone" => recipe.shadow = Some(first_token(value).parse()?),
+        "tonecurve" => parse_tone_curve(recipe, value)?,
+        "color" | "colour" | "saturation" => recipe.color = Some(first_token(value).parse()?),
+        "sharpness" | "sharpening" => recipe.sharpness = Some(first_token(value).parse()?),
+        "highisonr" | "highisonoisereduction" | "noisereduction" | "nr" => {
+            recipe.noise_reduction = Some(first_token(value).parse()?);
+        }
+        "clarity" => recipe.clarity = Some(first_token(value).parse()?),
+        "monochromaticcolor" | "monochromaticcolour" | "mc" => {
+            parse_monochromatic_color(recipe, value)?
+        }
+        "lensmodulationoptimizer" | "lmo" => {
+            recipe.lens_modulation_optimizer = Some(value.parse()?);
+        }
+        "colorspace" | "colourspace" => {
+            recipe.color_space = Some(parse_fuzzy("Color Space", value)?)
+        }
+        _ => warn!("Ignoring unknown recipe setting '{}'", key.trim()),
+    }
+
+    Ok(())
+}
+
+fn parse_fuzzy<T>(label: &str, value: &str) -> anyhow::Result<T>
+where
+    T: FromStr<Err = anyhow::Error> + Choices,
+{
+    match value.parse() {
+        Ok(value) => Ok(value),
+        Err(e) => {
+            if let Some(best) = T::closest(value)
+                && let Ok(parsed) = best.parse()
+            {
+                warn!("Interpreting {label} '{value}' as '{best}'");
+                return Ok(parsed);
+            }
+
+            Err(e)
+        }
+    }
+}
+
+fn parse_dynamic_range(value: &str) -> anyhow::Result<fuji::DynamicRange> {
+    let clean = value.clean().replace('-', "");
+    let level = clean
+        .strip_prefix("hdr")
+        .or_else(|| clean.strip_prefix("dr"))
+        .unwrap_or(&clean);
+
+    if level == "800" && value.trim().ends_with('+') {
+        return Ok(fuji::DynamicRange::HDR800Plus);
+    }
+
+    parse_fuzzy("Dynamic Range", level)
+}
+
+fn first_token(value: &str) -> &str {
+    value.split_whitespace().next().unwrap_or(value)
+}
+
+// Extracts the signed number attached to a marker such as "Red" in "+2 Red" or "R+2". Numbers
+// follow their markers in parts that start with a marker, and precede them otherwise.
+fn find_shift(value: &str, markers: &[&str]) -> Option<String> {
+    value.split(['&', ',', ';']).find_map(|part| {
+        let tokens = shift_tokens(part);
+        let numbers_follow = tokens.first().is_some_and(|token| !is_number(token));
+        let marker = tokens
+            .iter()
+            .position(|token| markers.contains(&token.as_str()))?;
+        let number = if numbers_follow {
+            tokens.get(marker + 1)
+        } else {
+            marker.checked_sub(1).and_then(|index| tokens.get(index))
+        };
+        number.filter(|token| is_number(token)).cloned()
+    })
+}
+
+// Splits a shift into lowercase words and signed numbers, dropping everything else.
+fn shift_tokens(value: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_alphabetic() {
+            let mut word: String = c.to_lowercase().collect();
+            while let Some(c) = chars.next_if(|c| c.is_alphabetic()) {
+                word.extend(c.to_lowercase());
+            }
+            tokens.push(word);
+        } else if c.is_ascii_digit()
+            || matches!(c, '+' | '-' | '\u{2212}') && chars.peek().is_some_and(char::is_ascii_digit)
+        {
+            let mut number = String::new();
+            if c.is_ascii_digit() {
+                number.push(c);
+            } else if c != '+' {
+                number.push('-');
+            }
+            while let Some(c) = chars.next_if(char::is_ascii_digit) {
+                number.push(c);
+            }
+            tokens.push(number);
+        }
+    }
+    tokens
+}
+
+fn is_number(token: &str) -> bool {
+    token.starts_with(|c: char| c.is_ascii_digit() || c == '-')
+}
+
+fn parse_white_balance(recipe: &mut Recipe, value: &str) -> anyhow::Result<()> {
+    let (mode, shifts) = value.split_once(',').unwrap_or((value, ""));
+    let mode = mode.trim();
+
+    let kelvin = mode.trim_end_matches(['K', 'k']);
+    if kelvin.len() < mode.len() && !kelvin.is_empty() && kelvin.chars().all(|c| c.is_ascii_digit())
+    {
+        recipe.white_balance = Some(fuji::WhiteBalance::Temperature);
+        recipe.white_balance_temperature = Some(kelvin.parse()?);
+    } else {
+        recipe.white_balance = Some(parse_fuzzy("White Balance", mode)?);
+    }
+
+    if let Some(red) = find_shift(shifts, &["red", "r"]) {
+        recipe.white_balance_shift_red = Some(red.parse()?);
+    }
+    if let Some(blue) = find_shift(shifts, &["blue", "b"]) {
+        recipe.white_balance_shift_blue = Some(blue.parse()?);
+    }
+
+    Ok(())
+}
+
+fn parse_tone_curve(recipe: &mut Recipe, value: &str) -> anyhow::Result<()> {
+    for token in value.split([' ', ',', '&']).map(str::trim) {
+        let lower = token.to_lowercase();
+        if let Some(highlight) = lower.strip_prefix('h') {
+            recipe.highlight = Some(highlight.parse()?);
+        } else if let Some(shadow) = lower.strip_prefix('s') {
+            recipe.shadow = Some(shadow.parse()?);
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_monochromatic_color(recipe: &mut Recipe, value: &str) -> anyhow::Result<()> {
+    if let Some(temperature) = find_shift(value, &["wc"]) {
+        recipe.monochromatic_color_temperature = Some(temperature.parse()?);
+    }
+    if let Some(tint) = find_shift(value, &["mg"]) {
+        recipe.monochromatic_color_tint = Some(tint.parse()?);
+    }
+
+    Ok(())
+}
+
+fn signed(value: impl ToString) -> String {
+    let value = value.to_string();
+    if value.starts_with('-') || value == "0" {
+        value
+    } else {
+        format!("+{value}")
+    }
+}
+
+fn grain_card(grain: fuji::GrainEffect) -> &'static str {
+    match grain {
+        fuji::GrainEffect::StrongLarge => "Strong, Large",
+        fuji::GrainEffect::WeakLarge => "Weak, Large",
+        fuji::GrainEffect::StrongSmall => "Strong, Small",
+        fuji::GrainEffect::WeakSmall => "Weak, Small",
+        fuji::GrainEffect::Off => "Off",
+    }
+}
+
+fn dynamic_range_card(dynamic_range: fuji::DynamicRange) -> &'static str {
+    match dynamic_range {
+        fuji::DynamicRange::Auto => "DR-Auto",
+        fuji::DynamicRange::HDR100 => "DR100",
+        fuji::DynamicRange::HDR200 => "DR200",
+        fuji::DynamicRange::HDR400 => "DR400",
+        fuji::DynamicRange::HDR800 => "DR800",
+        fuji::DynamicRange::HDR800Plus => "DR800+",
+    }
+}
+
+// Renders a recipe card in the same style `parse_card` accepts.
+pub fn write_card(recipe: &Recipe) -> anyhow::Result<String> {
+    let mut card = String::new();
+
+    if let Some(name) = &recipe.name {
+        writeln!(card, "Name: {name}")?;
+    }
+    if let Some(simulation) = recipe.simulation {
+        writeln!(card, "Film Simulation: {simulation}")?;
+    }
+    if let (Some(temperature), Some(tint)) = (
+        recipe.monochromatic_color_temperature,
+        recipe.monochromatic_color_tint,
+    ) && recipe
+        .simulation
+        .is_some_and(fuji::FilmSimulation::is_black_and_white)
+    {
+        writeln!(
+            card,
+            "Monochromatic Color: WC {}, MG {}",
+            signed(temperature),
+            signed(tint)
+        )?;
+    }
+    if let Some(grain) = recipe.grain {
+        writeln!(card, "Grain Effect: {}", grain_card(grain))?;
+    }
+    if let Some(effect) = recipe.color_chrome_effect {
+        writeln!(card, "Color Chrome Effect: {effect}")?;
+    }
+    if let Some(effect) = recipe.color_chrome_fx_blue {
+        writeln!(card, "Color Chrome FX Blue: {effect}")?;
+    }
+    if let Some(effect) = recipe.smooth_skin_effect {
+        writeln!(card, "Smooth Skin Effect: {effect}")?;
+    }
+    if let Some(white_balance) = recipe.white_balance {
+        let mode = match (white_balance, recipe.white_balance_temperature) {
+            (fuji::WhiteBalance::Temperature, Some(temperature)) => format!("{temperature}K"),
+            (white_balance, _) => white_balance.to_string(),
+        };
+        let red = recipe.white_balance_shift_red.map_or(0, |v| v.to_int());
+        let blue = recipe.white_balance_shift_blue.map_or(0, |v| v.to_int());
+        writeln!(
+            card,
+            "White Balance: {mode}, {} Red & {} Blue",
+            signed(red),
+            signed(blue)
+        )?;
+    }
+    if let Some(dynamic_range) = recipe.dynamic_range {
+        writeln!(card, "Dynamic Range: {}", dynamic_range_card(dynamic_range))?;
+    }
+    if let Some(priority) = recipe.dynamic_range_priority {
+        writeln!(card, "D-Range Priority: {priority}")?;
+    }
+    if let Some(highlight) = recipe.highlight {
+        writeln!(card, "Highlight: {}", signed(highlight))?;
+    }
+    if let Some(shadow) = recipe.shadow {
+        writeln!(card, "Shadow: {}", signed(shadow))?;
+    }
+    if let Some(color) = recipe.color {
+        writeln!(card, "Color: {}", signed(color))?;
+    }
+    if let Some(sharpness) = recipe.sharpness {
+        writeln!(card, "Sharpness: {}", signed(sharpness))?;
+    }
+    if let Some(noise_reduction) = recipe.noise_reduction {
+        writeln!(card, "High ISO NR: {noise_reduction}")?;
+    }
+    if let Some(clarity) = recipe.clarity {
+        writeln!(card, "Clarity: {}", signed(clarity))?;
+    }
+    if let Some(size) = recipe.size {
+        writeln!(card, "Image Size: {}", fuji::CanonicalImageSize::from(size))?;
+    }
+    if let Some(quality) = recipe.quality {
+        writeln!(card, "Image Quality: {quality}")?;
+    }
+    if let Some(optimizer) = recipe.lens_modulation_optimizer {
+        writeln!(card, "Lens Modulation Optimizer: {optimizer}")?;
+    }
+    if let Some(color_space) = recipe.color_space {
+        writeln!(card, "Color Space: {color_space}")?;
+    }
+
+    Ok(card)
+}
@@ -0,0 +1,215 @@
+use std::time::SystemTime;
+
+use anyhow::{Context, bail};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{features::simulation::SimulationFormat, ptp::fuji};
+
+pub const FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulationFileHeader {
+    pub format_version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_firmware: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exported_at: Option<String>,
+}
+
+impl SimulationFileHeader {
+    pub fn new(model: &str, firmware: Option<String>) -> Self {
+        Self {
+            format_version: FORMAT_VERSION,
+            source_model: Some(model.to_string()),
+            source_firmware: firmware,
+            exported_at: Some(humantime::format_rfc3339_seconds(SystemTime::now()).to_string()),
+        }
+    }
+
+    pub fn check_model(&self, model: &str) -> anyhow::Result<()> {
+        match &self.source_model {
+            Some(source) if source != model => {
+                bail!("Simulation file was exported from a {source}, but this camera is a {model}")
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn check_version(&self) -> anyhow::Result<()> {
+        if self.format_version > FORMAT_VERSION {
+            bail!(
+                "Simulation file format version {} is newer than the supported version {FORMAT_VERSION}",
+                self.format_version
+            );
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulationFile {
+    #[serde(flatten)]
+    pub header: SimulationFileHeader,
+    pub simulation: Value,
+}
+
+impl SimulationFile {
+    pub fn new(model: &str, firmware: Option<String>, simulation: &[u8]) -> anyhow::Result<Self> {
+        let simulation = serde_json::from_slice(simulation)?;
+
+        Ok(Self {
+            header: SimulationFileHeader::new(model, firmware),
+            simulation,
+        })
+    }
+
+    pub fn parse(data: &[u8], format: SimulationFormat) -> anyhow::Result<Self> {
+        let value = parse_value(data, format)?;
+
+        // NOTE: Version 0 files are the bare simulation written before the envelope existed.
+        let mut file = if value.get("formatVersion").is_some() {
+            serde_json::from_value::<Self>(value).context("Malformed simulation file header")?
+        } else {
+            Self {
+                header: SimulationFileHeader {
+                    format_version: 0,
+                    source_model: None,
+                    source_firmware: None,
+                    exported_at: None,
+                },
+                simulation: value,
+            }
+        };
+
+        file.header.check_version()?;
+
+        while file.header.format_version < FORMAT_VERSION {
+            debug!(
+                "Migrating simulation file from version {}",
+                file.header.format_version
+            );
+            migrate(file.header.format_version, &mut file.simulation)?;
+            file.header.format_version += 1;
+        }
+
+        Ok(file)
+    }
+
+    pub fn check_model(&self, model: &str) -> anyhow::Result<()> {
+        self.header.check_model(model)
+    }
+
+    pub fn to_vec(&self, format: SimulationFormat) -> anyhow::Result<Vec<u8>> {
+        to_vec(self, format)
+    }
+
+    // Removes and returns the name or path of the recipe this one is layered over.
+    pub fn take_extends(&mut self) -> anyhow::Result<Option<String>> {
+        let Some(extends) = self
+            .simulation
+            .as_object_mut()
+            .and_then(|simulation| simulation.remove("extends"))
+        else {
+            return Ok(None);
+        };
+
+        match extends {
+            Value::String(extends) => Ok(Some(extends)),
+            _ => bail!("'extends' must be a recipe name or path"),
+        }
+    }
+
+    pub fn simulation_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(serde_json::to_vec(&self.simulation)?)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleSlot {
+    pub slot: fuji::CustomSetting,
+    pub name: fuji::CustomSettingName,
+    pub simulation: Value,
+}
+
+// Every custom slot of a camera in a single file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulationBundle {
+    #[serde(flatten)]
+    pub header: SimulationFileHeader,
+    pub slots: Vec<BundleSlot>,
+}
+
+impl SimulationBundle {
+    pub fn parse(data: &[u8], format: SimulationFormat) -> anyhow::Result<Self> {
+        let value = parse_value(data, format)?;
+        if value.get("slots").is_none() {
+            bail!("Simulation file is not a bundle, import it into a single slot instead");
+        }
+
+        let mut bundle =
+            serde_json::from_value::<Self>(value).context("Malformed simulation bundle")?;
+        bundle.header.check_version()?;
+
+        while bundle.header.format_version < FORMAT_VERSION {
+            for slot in &mut bundle.slots {
+                migrate(bundle.header.format_version, &mut slot.simulation)?;
+            }
+            bundle.header.format_version += 1;
+        }
+
+        Ok(bundle)
+    }
+
+    pub fn to_vec(&self, format: SimulationFormat) -> anyhow::Result<Vec<u8>> {
+        to_vec(self, format)
+    }
+}
+
+fn parse_value(data: &[u8], format: SimulationFormat) -> anyhow::Result<Value> {
+    Ok(match format {
+        SimulationFormat::Json => {
+            serde_json::from_slice(data).context("Simulation file is not valid JSON")?
+        }
+        SimulationFormat::Toml => toml::from_str(std::str::from_utf8(data)?)
+            .context("Simulation file is not valid TOML")?,
+        SimulationFormat::Yaml => {
+            serde_yaml::from_slice(data).context("Simulation file is not valid YAML")?
+        }
+        SimulationFormat::Text => bail!("Recipe cards do not carry a simulation file header"),
+    })
+}
+
+fn to_vec<T: Serialize>(value: &T, format: SimulationFormat) -> anyhow::Result<Vec<u8>> {
+    Ok(match format {
+        SimulationFormat::Json => serde_json::to_vec_pretty(value)?,
+        SimulationFormat::Toml => toml::to_string_pretty(value)?.into_bytes(),
+        SimulationFormat::Yaml => serde_yaml::to_string(value)?.into_bytes(),
+        SimulationFormat::Text => bail!("Recipe cards do not carry a simulation file header"),
+    })
+}
+
+fn migrate(version: u32, simulation: &mut Value) -> anyhow::Result<()> {
+    match version {
+        // Version 0 stored the image size as pixel dimensions.
+        0 => {
+            if let Some(size) = simulation.get_mut("size")
+                && let Some(dimensions) = size.as_str()
+            {
+                let canonical = dimensions
+                    .parse::<fuji::CanonicalImageSize>()
+                    .with_context(|| format!("Cannot migrate image size '{dimensions}'"))?;
+                *size = Value::String(canonical.to_string());
+            }
+            Ok(())
+        }
+        _ => bail!("No migration from simulation file format version {version}"),
+    }
+}
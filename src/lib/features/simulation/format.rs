@@ -0,0 +1,65 @@
+use std::{fmt, path::Path, str::FromStr};
+
+use anyhow::bail;
+use strum_macros::EnumIter;
+
+use crate::input::{Choices, CleanAlphanumeric};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumIter)]
+pub enum SimulationFormat {
+    #[default]
+    Json,
+    Toml,
+    Yaml,
+    Text,
+}
+
+impl SimulationFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "toml" => Some(Self::Toml),
+            "yaml" | "yml" => Some(Self::Yaml),
+            "txt" | "text" | "md" => Some(Self::Text),
+            _ => None,
+        }
+    }
+
+    // An explicit format wins over the file extension, JSON is the fallback.
+    pub fn detect(format: Option<Self>, path: Option<&Path>) -> Self {
+        format
+            .or_else(|| path.and_then(Self::from_path))
+            .unwrap_or_default()
+    }
+}
+
+impl fmt::Display for SimulationFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json => write!(f, "json"),
+            Self::Toml => write!(f, "toml"),
+            Self::Yaml => write!(f, "yaml"),
+            Self::Text => write!(f, "text"),
+        }
+    }
+}
+
+impl FromStr for SimulationFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.clean().as_str() {
+            "json" => return Ok(Self::Json),
+            "toml" => return Ok(Self::Toml),
+            "yaml" | "yml" => return Ok(Self::Yaml),
+            "text" | "txt" | "card" | "md" | "markdown" => return Ok(Self::Text),
+            _ => {}
+        }
+
+        if let Some(best) = Self::closest(s) {
+            bail!("Unknown SimulationFormat '{s}'. Did you mean '{best}'?");
+        }
+
+        bail!("Unknown SimulationFormat '{s}'");
+    }
+}
@@ -0,0 +1,91 @@
+use std::{fmt, str::FromStr};
+
+use anyhow::bail;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+use crate::{
+    features::simulation::{Recipe, Simulation},
+    input,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter)]
+pub enum FieldGroup {
+    Tone,
+    Color,
+    WhiteBalance,
+    Effects,
+    Output,
+}
+
+impl fmt::Display for FieldGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tone => write!(f, "tone"),
+            Self::Color => write!(f, "color"),
+            Self::WhiteBalance => write!(f, "white-balance"),
+            Self::Effects => write!(f, "effects"),
+            Self::Output => write!(f, "output"),
+        }
+    }
+}
+
+// A field group, or a single field given by the names `get_field` accepts. Groups win where a
+// name is both, so "color" selects the color group rather than the Color field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldSelector {
+    Group(FieldGroup),
+    Field(String),
+}
+
+impl FromStr for FieldSelector {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let key = s.trim().to_lowercase().replace(['_', ' '], "-");
+
+        if let Some(group) = FieldGroup::iter().find(|group| group.to_string() == key) {
+            return Ok(Self::Group(group));
+        }
+
+        let fields = <dyn Simulation>::field_names();
+        if fields.contains(&key) {
+            return Ok(Self::Field(key));
+        }
+
+        let choices = FieldGroup::iter()
+            .map(|group| group.to_string())
+            .chain(fields);
+        if let Some(best) = input::closest(s, choices) {
+            bail!("Unknown field or group '{s}'. Did you mean '{best}'?");
+        }
+
+        bail!("Unknown field or group '{s}'");
+    }
+}
+
+impl fmt::Display for FieldSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Group(group) => write!(f, "{group}"),
+            Self::Field(field) => write!(f, "{field}"),
+        }
+    }
+}
+
+impl FieldSelector {
+    pub fn matches(&self, field: &str, group: Option<FieldGroup>) -> bool {
+        match self {
+            Self::Group(selected) => group == Some(*selected),
+            Self::Field(selected) => selected == field,
+        }
+    }
+}
+
+// Keeps only the fields selected by `only` (every field if empty), minus those in `except`.
+pub fn filter_recipe(recipe: &mut Recipe, only: &[FieldSelector], except: &[FieldSelector]) {
+    recipe.retain(|field, group| {
+        (only.is_empty() || only.iter().any(|selector| selector.matches(field, group)))
+            && !except.iter().any(|selector| selector.matches(field, group))
+    });
+}
@@ -0,0 +1,170 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    features::simulation::{
+        Recipe, SimulationFile,
+        envelope::{FORMAT_VERSION, SimulationFileHeader},
+    },
+    input::closest,
+};
+
+const EXTENSION: &str = "toml";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryRecipe {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    pub recipe: Recipe,
+}
+
+// Named recipes stored one TOML file each, independent of any camera model.
+#[derive(Debug, Clone)]
+pub struct RecipeLibrary {
+    root: PathBuf,
+}
+
+impl RecipeLibrary {
+    pub const fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    pub fn open_default() -> anyhow::Result<Self> {
+        let Some(data_dir) = dirs::data_dir() else {
+            bail!("Could not determine the user data directory");
+        };
+        Ok(Self::new(data_dir.join("fujicli").join("recipes")))
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn list(&self) -> anyhow::Result<Vec<LibraryRecipe>> {
+        let entries = match fs::read_dir(&self.root) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut recipes = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) == Some(EXTENSION) {
+                recipes.push(Self::read(&path)?);
+            }
+        }
+
+        recipes.sort_by_key(|recipe| recipe.name.to_lowercase());
+        Ok(recipes)
+    }
+
+    pub fn get(&self, name: &str) -> anyhow::Result<LibraryRecipe> {
+        Self::read(&self.locate(name)?)
+    }
+
+    // The library recipe as a simulation file, keeping any `extends` its recipe declares.
+    pub fn get_file(&self, name: &str) -> anyhow::Result<(PathBuf, SimulationFile)> {
+        let path = self.locate(name)?;
+        let (_, file) = Self::read_entry(&path)?;
+        Ok((path, file))
+    }
+
+    // The file a recipe is stored in, suggesting the closest name if there is none.
+    fn locate(&self, name: &str) -> anyhow::Result<PathBuf> {
+        let path = self.path(name)?;
+        if path.is_file() {
+            return Ok(path);
+        }
+
+        let names = self.list()?.into_iter().map(|recipe| recipe.name);
+        match closest(name, names) {
+            Some(suggestion) => {
+                bail!("No recipe named '{name}' in the library, did you mean '{suggestion}'?")
+            }
+            None => bail!("No recipe named '{name}' in the library"),
+        }
+    }
+
+    pub fn add(&self, recipe: &LibraryRecipe, overwrite: bool) -> anyhow::Result<()> {
+        let path = self.path(&recipe.name)?;
+        if !overwrite && path.exists() {
+            bail!(
+                "A recipe named '{}' already exists in the library",
+                recipe.name
+            );
+        }
+
+        fs::create_dir_all(&self.root)
+            .with_context(|| format!("Could not create library at {}", self.root.display()))?;
+        fs::write(&path, toml::to_string_pretty(recipe)?)
+            .with_context(|| format!("Could not write {}", path.display()))?;
+
+        Ok(())
+    }
+
+    pub fn remove(&self, name: &str) -> anyhow::Result<LibraryRecipe> {
+        let recipe = self.get(name)?;
+        fs::remove_file(self.path(&recipe.name)?)?;
+        Ok(recipe)
+    }
+
+    // Recipes may `extends` another, so every entry is read with its bases layered underneath.
+    fn read(path: &Path) -> anyhow::Result<LibraryRecipe> {
+        let (mut entry, file) = Self::read_entry(path)?;
+        let recipe = Recipe::from_file(file, path.parent())
+            .with_context(|| format!("Could not load recipe {}", path.display()))?;
+        entry.insert(String::from("recipe"), toml::Value::try_from(recipe)?);
+
+        toml::Value::Table(entry)
+            .try_into()
+            .with_context(|| format!("Malformed recipe {}", path.display()))
+    }
+
+    // An entry without its recipe table, which is returned as a simulation file.
+    fn read_entry(path: &Path) -> anyhow::Result<(toml::Table, SimulationFile)> {
+        let mut entry: toml::Table = toml::from_str(&fs::read_to_string(path)?)
+            .with_context(|| format!("Malformed recipe {}", path.display()))?;
+        let Some(recipe) = entry.remove("recipe") else {
+            bail!("Malformed recipe {}: missing recipe table", path.display());
+        };
+
+        let file = SimulationFile {
+            header: SimulationFileHeader {
+                format_version: FORMAT_VERSION,
+                source_model: None,
+                source_firmware: None,
+                exported_at: None,
+            },
+            simulation: serde_json::to_value(recipe)?,
+        };
+        Ok((entry, file))
+    }
+
+    fn path(&self, name: &str) -> anyhow::Result<PathBuf> {
+        let mut slug = String::new();
+        for c in name.trim().chars() {
+            if c.is_alphanumeric() {
+                slug.extend(c.to_lowercase());
+            } else if !slug.is_empty() && !slug.ends_with('-') {
+                slug.push('-');
+            }
+        }
+        let slug = slug.trim_end_matches('-');
+
+        if slug.is_empty() {
+            bail!("Recipe name '{name}' must contain at least one letter or digit");
+        }
+
+        Ok(self.root.join(format!("{slug}.{EXTENSION}")))
+    }
+}
@@ -1,11 +1,14 @@
+use anyhow::{Context, bail};
+use log::{error, info};
 use strum::IntoEnumIterator;
 
 use crate::{
     features::{
         base::CameraBase,
-        simulation::{Simulation, parser::CameraSimulationParser},
+        simulation::{Recipe, Simulation, SlotDrift, parser::CameraSimulationParser, rules},
     },
-    ptp::{Ptp, fuji},
+    input,
+    ptp::{DevicePropCode, PropWrite, Ptp, fuji},
 };
 
 pub trait CameraSimulationManager: CameraBase + CameraSimulationParser {
@@ -13,6 +16,54 @@ pub trait CameraSimulationManager: CameraBase + CameraSimulationParser {
         fuji::CustomSetting::iter().collect()
     }
 
+    fn slot_names(
+        &self,
+        ptp: &mut Ptp,
+    ) -> anyhow::Result<Vec<(fuji::CustomSetting, fuji::CustomSettingName)>> {
+        self.custom_settings_slots()
+            .into_iter()
+            .map(|slot| {
+                ptp.set_prop(DevicePropCode::FujiCustomSetting, &slot)?;
+                let name = ptp.get_prop(DevicePropCode::FujiCustomSettingName)?;
+                Ok((slot, name))
+            })
+            .collect()
+    }
+
+    // Finds the slot with the given custom setting name, ignoring case.
+    fn find_slot(&self, ptp: &mut Ptp, name: &str) -> anyhow::Result<fuji::CustomSetting> {
+        let names = self.slot_names(ptp)?;
+        let matches: Vec<fuji::CustomSetting> = names
+            .iter()
+            .filter(|(_, slot_name)| slot_name.trim().eq_ignore_ascii_case(name.trim()))
+            .map(|(slot, _)| *slot)
+            .collect();
+
+        match matches.as_slice() {
+            [slot] => Ok(*slot),
+            [] => {
+                let suggestion = input::closest(
+                    name,
+                    names.iter().map(|(_, slot_name)| slot_name.to_string()),
+                );
+                if let Some((slot, suggestion)) =
+                    suggestion.and_then(|suggestion| names.iter().find(|(_, n)| **n == suggestion))
+                {
+                    bail!("No slot named '{name}', did you mean '{suggestion}' ({slot})?");
+                }
+                bail!("No slot named '{name}'");
+            }
+            slots => bail!(
+                "More than one slot is named '{name}' ({}), use the slot number instead",
+                slots
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+
     fn get_simulation(
         &self,
         ptp: &mut Ptp,
@@ -32,4 +83,282 @@ pub trait CameraSimulationManager: CameraBase + CameraSimulationParser {
         slot: fuji::CustomSetting,
         simulation: &dyn Simulation,
     ) -> anyhow::Result<()>;
+
+    // The property writes that store a native simulation, in the order the camera expects them.
+    fn simulation_writes(&self, simulation: &dyn Simulation) -> anyhow::Result<Vec<PropWrite>>;
+
+    // Writes the properties of a native simulation that differ from the slot, then reads them
+    // back. The slot is restored if a write fails or the camera reports a different value.
+    fn write_simulation(
+        &self,
+        ptp: &mut Ptp,
+        slot: fuji::CustomSetting,
+        simulation: &dyn Simulation,
+    ) -> anyhow::Result<()> {
+        let current = self.get_simulation(ptp, slot)?;
+        rules::enforce(&rules::check_changes(
+            &current.to_recipe(),
+            &simulation.to_recipe(),
+            self.camera_definition().name,
+            self.supported_values(),
+        ))?;
+
+        let snapshot = self.simulation_writes(&*current)?;
+        let target = self.simulation_writes(simulation)?;
+        let writes = changed_props(&snapshot, target.clone());
+
+        info!(
+            "Writing {} of {} properties to slot {slot}",
+            writes.len(),
+            target.len()
+        );
+        if writes.is_empty() {
+            return Ok(());
+        }
+
+        if let Err(e) = write_props(ptp, slot, &writes) {
+            error!("Error writing slot {slot}: {e}. Restoring previous options.");
+            let restore: Vec<PropWrite> = snapshot
+                .into_iter()
+                .filter(|original| writes.iter().any(|write| write.code == original.code))
+                .collect();
+            write_props(ptp, slot, &restore)
+                .with_context(|| format!("Could not restore slot {slot}"))?;
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    fn validate_simulation(&self, simulation: &dyn Simulation) -> anyhow::Result<()> {
+        simulation.validate(self.camera_definition().name, self.supported_values())
+    }
+
+    // Builds a native simulation from one of a different model, on top of the slot's current settings.
+    fn convert_simulation(
+        &self,
+        ptp: &mut Ptp,
+        slot: fuji::CustomSetting,
+        simulation: &dyn Simulation,
+    ) -> anyhow::Result<Box<dyn Simulation>> {
+        let current = self.get_simulation(ptp, slot)?;
+        self.convert_onto(current, simulation)
+    }
+
+    // Builds a native simulation from one of a different model, on top of `base`.
+    fn convert_onto(
+        &self,
+        mut base: Box<dyn Simulation>,
+        simulation: &dyn Simulation,
+    ) -> anyhow::Result<Box<dyn Simulation>> {
+        simulation.to_recipe().apply_to_simulation(
+            &mut *base,
+            self.camera_definition().name,
+            self.supported_values(),
+        )?;
+        Ok(base)
+    }
+
+    // Compares each slot with the recipe it should hold, returning only the slots that differ.
+    fn plan_sync(
+        &self,
+        ptp: &mut Ptp,
+        desired: &[(fuji::CustomSetting, Recipe)],
+    ) -> anyhow::Result<Vec<SlotDrift>> {
+        let mut plan = Vec::new();
+
+        for (slot, recipe) in desired {
+            let current = self.get_simulation(ptp, *slot)?;
+            let base = self.deserialize_simulation(&self.serialize_simulation(&*current)?)?;
+            let target = self.convert_onto(base, recipe)?;
+            self.validate_simulation(&*target)
+                .with_context(|| format!("Slot {slot} cannot hold the desired simulation"))?;
+
+            // Fields the camera ignores can differ without the slot behaving any differently.
+            let differences = current
+                .to_recipe()
+                .without_ignored()
+                .diff(&target.to_recipe().without_ignored());
+            if !differences.is_empty() {
+                plan.push(SlotDrift {
+                    slot: *slot,
+                    differences,
+                    current,
+                    target,
+                });
+            }
+        }
+
+        Ok(plan)
+    }
+
+    // Writes every planned slot, restoring all of them if any write fails.
+    fn apply_sync(&self, ptp: &mut Ptp, plan: &[SlotDrift]) -> anyhow::Result<()> {
+        let writes: Vec<SlotWrite> = plan
+            .iter()
+            .map(|drift| (drift.slot, &*drift.target, &*drift.current))
+            .collect();
+        self.write_slots(ptp, &writes)
+    }
+
+    // The settings a slot holds after a factory reset, as reported by the camera.
+    fn default_simulation(
+        &self,
+        ptp: &mut Ptp,
+        slot: fuji::CustomSetting,
+    ) -> anyhow::Result<Box<dyn Simulation>>;
+
+    fn copy_simulation(
+        &self,
+        ptp: &mut Ptp,
+        from: fuji::CustomSetting,
+        to: fuji::CustomSetting,
+    ) -> anyhow::Result<()> {
+        let source = self.get_simulation(ptp, from)?;
+        let original = self.get_simulation(ptp, to)?;
+        self.write_slots(ptp, &[(to, &*source, &*original)])
+    }
+
+    fn swap_simulations(
+        &self,
+        ptp: &mut Ptp,
+        a: fuji::CustomSetting,
+        b: fuji::CustomSetting,
+    ) -> anyhow::Result<()> {
+        let simulation_a = self.get_simulation(ptp, a)?;
+        let simulation_b = self.get_simulation(ptp, b)?;
+        self.write_slots(
+            ptp,
+            &[
+                (a, &*simulation_b, &*simulation_a),
+                (b, &*simulation_a, &*simulation_b),
+            ],
+        )
+    }
+
+    fn move_simulation(
+        &self,
+        ptp: &mut Ptp,
+        from: fuji::CustomSetting,
+        to: fuji::CustomSetting,
+    ) -> anyhow::Result<()> {
+        let source = self.get_simulation(ptp, from)?;
+        let original = self.get_simulation(ptp, to)?;
+        let default = self.default_simulation(ptp, from)?;
+        self.write_slots(
+            ptp,
+            &[(to, &*source, &*original), (from, &*default, &*source)],
+        )
+    }
+
+    fn reset_simulation(&self, ptp: &mut Ptp, slot: fuji::CustomSetting) -> anyhow::Result<()> {
+        let original = self.get_simulation(ptp, slot)?;
+        let default = self.default_simulation(ptp, slot)?;
+        self.write_slots(ptp, &[(slot, &*default, &*original)])
+    }
+
+    // Writes each slot in order. If any write fails, every slot written so far is restored.
+    fn write_slots(&self, ptp: &mut Ptp, writes: &[SlotWrite]) -> anyhow::Result<()> {
+        for (index, (slot, target, _)) in writes.iter().enumerate() {
+            if let Err(e) = self.set_simulation(ptp, *slot, *target) {
+                error!("Error writing slot {slot}: {e}. Restoring previous options.");
+
+                for (slot, _, original) in writes[..=index].iter().rev() {
+                    if let Err(e) = self.set_simulation(ptp, *slot, *original) {
+                        error!("Could not restore slot {slot}: {e}");
+                    }
+                }
+
+                return Err(e.context(format!("Could not write slot {slot}")));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Properties that must be rewritten whenever the property they depend on is written.
+const WRITE_DEPENDENCIES: &[(DevicePropCode, &[DevicePropCode])] = &[
+    (
+        DevicePropCode::FujiCustomSettingFilmSimulation,
+        &[
+            DevicePropCode::FujiCustomSettingMonochromaticColorTemperature,
+            DevicePropCode::FujiCustomSettingMonochromaticColorTint,
+        ],
+    ),
+    (
+        DevicePropCode::FujiCustomSettingWhiteBalance,
+        &[
+            DevicePropCode::FujiCustomSettingWhiteBalanceShiftRed,
+            DevicePropCode::FujiCustomSettingWhiteBalanceShiftBlue,
+            DevicePropCode::FujiCustomSettingWhiteBalanceTemperature,
+        ],
+    ),
+    (
+        DevicePropCode::FujiCustomSettingDynamicRangePriority,
+        &[DevicePropCode::FujiCustomSettingDynamicRange],
+    ),
+];
+
+// Keeps the target writes whose value differs from the current one, plus their dependents.
+// Writes keep the target order, which devices list dependencies first.
+fn changed_props(current: &[PropWrite], target: Vec<PropWrite>) -> Vec<PropWrite> {
+    let changed: Vec<DevicePropCode> = target
+        .iter()
+        .filter(|write| {
+            !current
+                .iter()
+                .any(|original| original.code == write.code && original.value == write.value)
+        })
+        .map(|write| write.code)
+        .collect();
+
+    target
+        .into_iter()
+        .filter(|write| {
+            changed.contains(&write.code)
+                || WRITE_DEPENDENCIES.iter().any(|(parent, dependents)| {
+                    changed.contains(parent) && dependents.contains(&write.code)
+                })
+        })
+        .collect()
 }
+
+fn write_props(
+    ptp: &mut Ptp,
+    slot: fuji::CustomSetting,
+    writes: &[PropWrite],
+) -> anyhow::Result<()> {
+    ptp.set_prop(DevicePropCode::FujiCustomSetting, &slot)?;
+
+    for write in writes {
+        ptp.write_prop(write)
+            .with_context(|| format!("Camera rejected {write}"))?;
+    }
+
+    if ptp.dry_run {
+        return Ok(());
+    }
+
+    let mut mismatches = Vec::new();
+    for write in writes {
+        if let Some(actual) = ptp.verify_prop(write)? {
+            mismatches.push(format!(
+                "{:?}: wrote '{}', camera reports '{actual}'",
+                write.code, write.display
+            ));
+        }
+    }
+
+    if !mismatches.is_empty() {
+        bail!(
+            "Slot {slot} did not keep every value:\n  {}",
+            mismatches.join("\n  ")
+        );
+    }
+
+    Ok(())
+}
+
+// A slot, the simulation to write to it and the simulation to restore on failure.
+pub type SlotWrite<'a> = (fuji::CustomSetting, &'a dyn Simulation, &'a dyn Simulation);
@@ -0,0 +1,101 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    features::simulation::{Recipe, RecipeDifference, RecipeLibrary, Simulation, SimulationFormat},
+    ptp::fuji,
+};
+
+// The desired contents of one slot: a library recipe or file, optionally overridden inline.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlotManifestEntry {
+    #[serde(default)]
+    pub recipe: Option<String>,
+    #[serde(default)]
+    pub file: Option<PathBuf>,
+    #[serde(flatten)]
+    pub settings: Recipe,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SlotManifest {
+    #[serde(default)]
+    pub slots: BTreeMap<String, SlotManifestEntry>,
+}
+
+impl SlotManifest {
+    pub fn parse(data: &[u8], format: SimulationFormat) -> anyhow::Result<Self> {
+        Ok(match format {
+            SimulationFormat::Json => {
+                serde_json::from_slice(data).context("Manifest is not valid JSON")?
+            }
+            SimulationFormat::Toml => {
+                toml::from_str(std::str::from_utf8(data)?).context("Manifest is not valid TOML")?
+            }
+            SimulationFormat::Yaml => {
+                serde_yaml::from_slice(data).context("Manifest is not valid YAML")?
+            }
+            SimulationFormat::Text => bail!("Manifests cannot be written as recipe cards"),
+        })
+    }
+
+    pub fn load(path: &Path, format: Option<SimulationFormat>) -> anyhow::Result<Self> {
+        let data = fs::read(path).with_context(|| format!("Could not read {}", path.display()))?;
+        Self::parse(&data, SimulationFormat::detect(format, Some(path)))
+    }
+
+    // Resolves every slot to a recipe. Relative file paths are taken from `base_dir`.
+    pub fn resolve(
+        &self,
+        library: &RecipeLibrary,
+        base_dir: &Path,
+    ) -> anyhow::Result<Vec<(fuji::CustomSetting, Recipe)>> {
+        let mut resolved: Vec<(fuji::CustomSetting, Recipe)> = Vec::new();
+
+        for (key, entry) in &self.slots {
+            let slot: fuji::CustomSetting = key.parse()?;
+            if resolved.iter().any(|(other, _)| *other == slot) {
+                bail!("Slot {slot} appears more than once in the manifest");
+            }
+
+            let mut recipe = match (&entry.recipe, &entry.file) {
+                (Some(_), Some(_)) => {
+                    bail!("Slot {slot} cannot use both a library recipe and a file")
+                }
+                (Some(name), None) => library.get(name)?.recipe,
+                (None, Some(file)) => {
+                    let path = base_dir.join(file);
+                    let data = fs::read(&path)
+                        .with_context(|| format!("Could not read {}", path.display()))?;
+                    let format = SimulationFormat::detect(None, Some(&path));
+                    Recipe::from_bytes(&data, format, path.parent())?
+                }
+                (None, None) => Recipe::default(),
+            };
+            recipe.merge(&entry.settings);
+
+            resolved.push((slot, recipe));
+        }
+
+        Ok(resolved)
+    }
+}
+
+// A slot whose contents differ from the manifest.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlotDrift {
+    pub slot: fuji::CustomSetting,
+    pub differences: Vec<RecipeDifference>,
+    #[serde(skip)]
+    pub current: Box<dyn Simulation>,
+    #[serde(skip)]
+    pub target: Box<dyn Simulation>,
+}
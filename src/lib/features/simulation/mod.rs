@@ -1,8 +1,25 @@
+pub mod card;
+pub mod envelope;
+pub mod format;
+pub mod groups;
+pub mod library;
 pub mod manager;
+pub mod manifest;
 pub mod parser;
-
+pub mod recipe;
+pub mod rules;
+pub mod values;
+
+pub use envelope::{BundleSlot, SimulationBundle, SimulationFile};
+pub use format::SimulationFormat;
+pub use groups::{FieldGroup, FieldSelector};
+pub use library::{LibraryRecipe, RecipeLibrary};
 pub use manager::CameraSimulationManager;
+pub use manifest::{SlotDrift, SlotManifest};
 pub use parser::CameraSimulationParser;
+pub use recipe::{Recipe, RecipeDifference};
+pub use rules::{RuleViolation, Severity};
+pub use values::{ModelImageSize, SupportedValues};
 
 use std::{any::Any, fmt};
 
@@ -16,7 +33,7 @@ macro_rules! getter {
         fn $name(&self) -> anyhow::Result<$type> {
             anyhow::bail!(
                 "This simulation profile does not support getting {}",
-                stringify!($ty)
+                stringify!($type)
             );
         }
     };
@@ -27,7 +44,7 @@ macro_rules! setter {
         fn $name(&mut self, _value: &$type) -> anyhow::Result<()> {
             anyhow::bail!(
                 "This simulation profile does not support setting {}",
-                stringify!($ty)
+                stringify!($type)
             );
         }
     };
@@ -41,7 +58,39 @@ pub trait Simulation: fmt::Display + erased_serde::Serialize {
     where
         Self: Sized;
 
+    fn validate(&self, _model: &str, _values: &SupportedValues) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn to_recipe(&self) -> Recipe;
+
     getter!(get_name, fuji::CustomSettingName);
+    getter!(get_size, fuji::ImageSize);
+    getter!(get_quality, fuji::ImageQuality);
+    getter!(get_simulation, fuji::FilmSimulation);
+    getter!(
+        get_monochromatic_color_temperature,
+        fuji::MonochromaticColorShift
+    );
+    getter!(get_monochromatic_color_tint, fuji::MonochromaticColorShift);
+    getter!(get_highlight, fuji::HighlightTone);
+    getter!(get_shadow, fuji::ShadowTone);
+    getter!(get_color, fuji::Color);
+    getter!(get_sharpness, fuji::Sharpness);
+    getter!(get_clarity, fuji::Clarity);
+    getter!(get_noise_reduction, fuji::NoiseReduction);
+    getter!(get_grain, fuji::GrainEffect);
+    getter!(get_color_chrome_effect, fuji::ColorChromeEffect);
+    getter!(get_color_chrome_fx_blue, fuji::ColorChromeFXBlue);
+    getter!(get_smooth_skin_effect, fuji::SmoothSkinEffect);
+    getter!(get_white_balance, fuji::WhiteBalance);
+    getter!(get_white_balance_shift_red, fuji::WhiteBalanceShift);
+    getter!(get_white_balance_shift_blue, fuji::WhiteBalanceShift);
+    getter!(get_white_balance_temperature, fuji::WhiteBalanceTemperature);
+    getter!(get_dynamic_range, fuji::DynamicRange);
+    getter!(get_dynamic_range_priority, fuji::DynamicRangePriority);
+    getter!(get_lens_modulation_optimizer, fuji::LensModulationOptimizer);
+    getter!(get_color_space, fuji::ColorSpace);
 
     setter!(set_name, fuji::CustomSettingName);
     setter!(set_size, fuji::ImageSize);
@@ -81,6 +130,14 @@ pub struct SimulationListItem {
     pub name: fuji::CustomSettingName,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulationListEntry {
+    pub slot: fuji::CustomSetting,
+    #[serde(flatten)]
+    pub simulation: Box<dyn Simulation>,
+}
+
 impl fmt::Display for SimulationListItem {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}: {}", self.slot, self.name)
@@ -111,6 +168,17 @@ macro_rules! impl_simulation {
                 Ok(Box::new(s))
             }
 
+            fn to_recipe(&self) -> crate::features::simulation::Recipe {
+                let mut recipe = crate::features::simulation::Recipe {
+                    name: Some(self.name.clone()),
+                    ..Default::default()
+                };
+                $(
+                    crate::features::simulation::impl_simulation!(@recipe self, recipe, $cap);
+                )*
+                recipe
+            }
+
             fn get_name(&self) -> anyhow::Result<crate::ptp::fuji::CustomSettingName> {
                 Ok(self.name.clone())
             }
@@ -120,6 +188,17 @@ macro_rules! impl_simulation {
                 Ok(())
             }
 
+            fn validate(
+                &self,
+                model: &str,
+                values: &crate::features::simulation::SupportedValues,
+            ) -> anyhow::Result<()> {
+                $(
+                    crate::features::simulation::impl_simulation!(@validate self, model, values, $cap);
+                )*
+                Ok(())
+            }
+
             $(
                 crate::features::simulation::impl_simulation!(@cap self, $cap);
             )*
@@ -137,39 +216,93 @@ macro_rules! impl_simulation {
     };
 
     (@cap $self:ident, ImageSize) => {
+        fn get_size(&$self) -> anyhow::Result<crate::ptp::fuji::ImageSize> {
+            Ok($self.size)
+        }
+
         fn set_size(&mut $self, value: &crate::ptp::fuji::ImageSize) -> anyhow::Result<()> {
             $self.size = *value;
             Ok(())
         }
     };
 
+    (@validate $self:ident, $model:ident, $values:ident, ImageSize) => {
+        $values.image_size($model, crate::ptp::fuji::CanonicalImageSize::from($self.size))?;
+    };
+
+    (@recipe $self:ident, $recipe:ident, ImageSize) => {
+        $recipe.size = Some($self.size);
+    };
+
     (@write $self:ident, $f:ident, ImageSize) => {
-        writeln!($f, "Size: {}", $self.size)?;
+        writeln!(
+            $f,
+            "Size: {}",
+            crate::ptp::fuji::CanonicalImageSize::from($self.size)
+        )?;
     };
 
     (@cap $self:ident, ImageQuality) => {
+        fn get_quality(&$self) -> anyhow::Result<crate::ptp::fuji::ImageQuality> {
+            Ok($self.quality)
+        }
+
         fn set_quality(&mut $self, value: &crate::ptp::fuji::ImageQuality) -> anyhow::Result<()> {
             $self.quality = *value;
             Ok(())
         }
     };
 
+    (@validate $self:ident, $model:ident, $values:ident, ImageQuality) => {
+        crate::features::simulation::values::check(
+            $model,
+            "Quality",
+            $values.image_qualities,
+            &$self.quality,
+        )?;
+    };
+
+    (@recipe $self:ident, $recipe:ident, ImageQuality) => {
+        $recipe.quality = Some($self.quality);
+    };
+
     (@write $self:ident, $f:ident, ImageQuality) => {
         writeln!($f, "Quality: {}", $self.quality)?;
     };
 
     (@cap $self:ident, FilmSimulation) => {
+        fn get_simulation(&$self) -> anyhow::Result<crate::ptp::fuji::FilmSimulation> {
+            Ok($self.simulation)
+        }
+
         fn set_simulation(&mut $self, value: &crate::ptp::fuji::FilmSimulation) -> anyhow::Result<()> {
             $self.simulation = *value;
             Ok(())
         }
     };
 
+    (@validate $self:ident, $model:ident, $values:ident, FilmSimulation) => {
+        crate::features::simulation::values::check(
+            $model,
+            "Simulation",
+            $values.film_simulations,
+            &$self.simulation,
+        )?;
+    };
+
+    (@recipe $self:ident, $recipe:ident, FilmSimulation) => {
+        $recipe.simulation = Some($self.simulation);
+    };
+
     (@write $self:ident, $f:ident, FilmSimulation) => {
         writeln!($f, "Simulation: {}", $self.simulation)?;
     };
 
     (@cap $self:ident, MonochromaticColorShift) => {
+        fn get_monochromatic_color_temperature(&$self) -> anyhow::Result<crate::ptp::fuji::MonochromaticColorShift> {
+            Ok($self.monochromatic_color_temperature)
+        }
+
         fn set_monochromatic_color_temperature(
             &mut $self,
             value: &crate::ptp::fuji::MonochromaticColorShift
@@ -178,6 +311,10 @@ macro_rules! impl_simulation {
             Ok(())
         }
 
+        fn get_monochromatic_color_tint(&$self) -> anyhow::Result<crate::ptp::fuji::MonochromaticColorShift> {
+            Ok($self.monochromatic_color_tint)
+        }
+
         fn set_monochromatic_color_tint(
             &mut $self,
             value: &crate::ptp::fuji::MonochromaticColorShift
@@ -187,89 +324,163 @@ macro_rules! impl_simulation {
         }
     };
 
+    (@recipe $self:ident, $recipe:ident, MonochromaticColorShift) => {
+        $recipe.monochromatic_color_temperature = Some($self.monochromatic_color_temperature);
+        $recipe.monochromatic_color_tint = Some($self.monochromatic_color_tint);
+    };
+
     (@write $self:ident, $f:ident, MonochromaticColorShift) => {
         writeln!($f, "Monochromatic Color Temperature: {}", $self.monochromatic_color_temperature)?;
         writeln!($f, "Monochromatic Color Tint: {}", $self.monochromatic_color_tint)?;
     };
 
     (@cap $self:ident, Highlight) => {
+        fn get_highlight(&$self) -> anyhow::Result<crate::ptp::fuji::HighlightTone> {
+            Ok($self.highlight)
+        }
+
         fn set_highlight(&mut $self, value: &crate::ptp::fuji::HighlightTone) -> anyhow::Result<()> {
             $self.highlight = *value;
             Ok(())
         }
     };
 
+    (@recipe $self:ident, $recipe:ident, Highlight) => {
+        $recipe.highlight = Some($self.highlight);
+    };
+
     (@write $self:ident, $f:ident, Highlight) => {
         writeln!($f, "Highlight: {}", $self.highlight)?;
     };
 
     (@cap $self:ident, Shadow) => {
+        fn get_shadow(&$self) -> anyhow::Result<crate::ptp::fuji::ShadowTone> {
+            Ok($self.shadow)
+        }
+
         fn set_shadow(&mut $self, value: &crate::ptp::fuji::ShadowTone) -> anyhow::Result<()> {
             $self.shadow = *value;
             Ok(())
         }
     };
 
+    (@recipe $self:ident, $recipe:ident, Shadow) => {
+        $recipe.shadow = Some($self.shadow);
+    };
+
     (@write $self:ident, $f:ident, Shadow) => {
         writeln!($f, "Shadow: {}", $self.shadow)?;
     };
 
     (@cap $self:ident, Color) => {
+        fn get_color(&$self) -> anyhow::Result<crate::ptp::fuji::Color> {
+            Ok($self.color)
+        }
+
         fn set_color(&mut $self, value: &crate::ptp::fuji::Color) -> anyhow::Result<()> {
             $self.color = *value;
             Ok(())
         }
     };
 
+    (@recipe $self:ident, $recipe:ident, Color) => {
+        $recipe.color = Some($self.color);
+    };
+
     (@write $self:ident, $f:ident, Color) => {
         writeln!($f, "Color: {}", $self.color)?;
     };
 
     (@cap $self:ident, Sharpness) => {
+        fn get_sharpness(&$self) -> anyhow::Result<crate::ptp::fuji::Sharpness> {
+            Ok($self.sharpness)
+        }
+
         fn set_sharpness(&mut $self, value: &crate::ptp::fuji::Sharpness) -> anyhow::Result<()> {
             $self.sharpness = *value;
             Ok(())
         }
     };
 
+    (@recipe $self:ident, $recipe:ident, Sharpness) => {
+        $recipe.sharpness = Some($self.sharpness);
+    };
+
     (@write $self:ident, $f:ident, Sharpness) => {
         writeln!($f, "Sharpness: {}", $self.sharpness)?;
     };
 
     (@cap $self:ident, Clarity) => {
+        fn get_clarity(&$self) -> anyhow::Result<crate::ptp::fuji::Clarity> {
+            Ok($self.clarity)
+        }
+
         fn set_clarity(&mut $self, value: &crate::ptp::fuji::Clarity) -> anyhow::Result<()> {
             $self.clarity = *value;
             Ok(())
         }
     };
 
+    (@recipe $self:ident, $recipe:ident, Clarity) => {
+        $recipe.clarity = Some($self.clarity);
+    };
+
     (@write $self:ident, $f:ident, Clarity) => {
         writeln!($f, "Clarity: {}", $self.clarity)?;
     };
 
     (@cap $self:ident, NoiseReduction) => {
+        fn get_noise_reduction(&$self) -> anyhow::Result<crate::ptp::fuji::NoiseReduction> {
+            Ok($self.noise_reduction)
+        }
+
         fn set_noise_reduction(&mut $self, value: &crate::ptp::fuji::NoiseReduction) -> anyhow::Result<()> {
             $self.noise_reduction = *value;
             Ok(())
         }
     };
 
+    (@recipe $self:ident, $recipe:ident, NoiseReduction) => {
+        $recipe.noise_reduction = Some($self.noise_reduction);
+    };
+
     (@write $self:ident, $f:ident, NoiseReduction) => {
         writeln!($f, "Noise Reduction: {}", $self.noise_reduction)?;
     };
 
     (@cap $self:ident, GrainEffect) => {
+        fn get_grain(&$self) -> anyhow::Result<crate::ptp::fuji::GrainEffect> {
+            Ok($self.grain)
+        }
+
         fn set_grain(&mut $self, value: &crate::ptp::fuji::GrainEffect) -> anyhow::Result<()> {
             $self.grain = *value;
             Ok(())
         }
     };
 
+    (@validate $self:ident, $model:ident, $values:ident, GrainEffect) => {
+        crate::features::simulation::values::check(
+            $model,
+            "Grain",
+            $values.grain_effects,
+            &$self.grain,
+        )?;
+    };
+
+    (@recipe $self:ident, $recipe:ident, GrainEffect) => {
+        $recipe.grain = Some($self.grain);
+    };
+
     (@write $self:ident, $f:ident, GrainEffect) => {
         writeln!($f, "Grain: {}", $self.grain)?;
     };
 
     (@cap $self:ident, ColorChromeEffect) => {
+        fn get_color_chrome_effect(&$self) -> anyhow::Result<crate::ptp::fuji::ColorChromeEffect> {
+            Ok($self.color_chrome_effect)
+        }
+
         fn set_color_chrome_effect(
             &mut $self,
             value: &crate::ptp::fuji::ColorChromeEffect
@@ -279,11 +490,19 @@ macro_rules! impl_simulation {
         }
     };
 
+    (@recipe $self:ident, $recipe:ident, ColorChromeEffect) => {
+        $recipe.color_chrome_effect = Some($self.color_chrome_effect);
+    };
+
     (@write $self:ident, $f:ident, ColorChromeEffect) => {
         writeln!($f, "Color Chrome Effect: {}", $self.color_chrome_effect)?;
     };
 
     (@cap $self:ident, ColorChromeFXBlue) => {
+        fn get_color_chrome_fx_blue(&$self) -> anyhow::Result<crate::ptp::fuji::ColorChromeFXBlue> {
+            Ok($self.color_chrome_fx_blue)
+        }
+
         fn set_color_chrome_fx_blue(
             &mut $self,
             value: &crate::ptp::fuji::ColorChromeFXBlue
@@ -293,11 +512,19 @@ macro_rules! impl_simulation {
         }
     };
 
+    (@recipe $self:ident, $recipe:ident, ColorChromeFXBlue) => {
+        $recipe.color_chrome_fx_blue = Some($self.color_chrome_fx_blue);
+    };
+
     (@write $self:ident, $f:ident, ColorChromeFXBlue) => {
         writeln!($f, "Color Chrome FX Blue: {}", $self.color_chrome_fx_blue)?;
     };
 
     (@cap $self:ident, SmoothSkinEffect) => {
+        fn get_smooth_skin_effect(&$self) -> anyhow::Result<crate::ptp::fuji::SmoothSkinEffect> {
+            Ok($self.smooth_skin_effect)
+        }
+
         fn set_smooth_skin_effect(
             &mut $self,
             value: &crate::ptp::fuji::SmoothSkinEffect
@@ -307,22 +534,47 @@ macro_rules! impl_simulation {
         }
     };
 
+    (@recipe $self:ident, $recipe:ident, SmoothSkinEffect) => {
+        $recipe.smooth_skin_effect = Some($self.smooth_skin_effect);
+    };
+
     (@write $self:ident, $f:ident, SmoothSkinEffect) => {
         writeln!($f, "Smooth Skin Effect: {}", $self.smooth_skin_effect)?;
     };
 
     (@cap $self:ident, WhiteBalance) => {
+        fn get_white_balance(&$self) -> anyhow::Result<crate::ptp::fuji::WhiteBalance> {
+            Ok($self.white_balance)
+        }
+
         fn set_white_balance(&mut $self, value: &crate::ptp::fuji::WhiteBalance) -> anyhow::Result<()> {
             $self.white_balance = *value;
             Ok(())
         }
     };
 
+    (@validate $self:ident, $model:ident, $values:ident, WhiteBalance) => {
+        crate::features::simulation::values::check(
+            $model,
+            "White Balance",
+            $values.white_balances,
+            &$self.white_balance,
+        )?;
+    };
+
+    (@recipe $self:ident, $recipe:ident, WhiteBalance) => {
+        $recipe.white_balance = Some($self.white_balance);
+    };
+
     (@write $self:ident, $f:ident, WhiteBalance) => {
         writeln!($f, "White Balance: {}", $self.white_balance)?;
     };
 
     (@cap $self:ident, WhiteBalanceShift) => {
+        fn get_white_balance_shift_red(&$self) -> anyhow::Result<crate::ptp::fuji::WhiteBalanceShift> {
+            Ok($self.white_balance_shift_red)
+        }
+
         fn set_white_balance_shift_red(
             &mut $self,
             value: &crate::ptp::fuji::WhiteBalanceShift
@@ -331,6 +583,10 @@ macro_rules! impl_simulation {
             Ok(())
         }
 
+        fn get_white_balance_shift_blue(&$self) -> anyhow::Result<crate::ptp::fuji::WhiteBalanceShift> {
+            Ok($self.white_balance_shift_blue)
+        }
+
         fn set_white_balance_shift_blue(
             &mut $self,
             value: &crate::ptp::fuji::WhiteBalanceShift
@@ -340,6 +596,11 @@ macro_rules! impl_simulation {
         }
     };
 
+    (@recipe $self:ident, $recipe:ident, WhiteBalanceShift) => {
+        $recipe.white_balance_shift_red = Some($self.white_balance_shift_red);
+        $recipe.white_balance_shift_blue = Some($self.white_balance_shift_blue);
+    };
+
     (@write $self:ident, $f:ident, WhiteBalanceShift) => {
         writeln!(
             $f,
@@ -350,6 +611,10 @@ macro_rules! impl_simulation {
     };
 
     (@cap $self:ident, WhiteBalanceTemperature) => {
+        fn get_white_balance_temperature(&$self) -> anyhow::Result<crate::ptp::fuji::WhiteBalanceTemperature> {
+            Ok($self.white_balance_temperature)
+        }
+
         fn set_white_balance_temperature(
             &mut $self,
             value: &crate::ptp::fuji::WhiteBalanceTemperature
@@ -359,22 +624,47 @@ macro_rules! impl_simulation {
         }
     };
 
+    (@recipe $self:ident, $recipe:ident, WhiteBalanceTemperature) => {
+        $recipe.white_balance_temperature = Some($self.white_balance_temperature);
+    };
+
     (@write $self:ident, $f:ident, WhiteBalanceTemperature) => {
         writeln!($f, "White Balance Temperature: {}K", $self.white_balance_temperature)?;
     };
 
     (@cap $self:ident, DynamicRange) => {
+        fn get_dynamic_range(&$self) -> anyhow::Result<crate::ptp::fuji::DynamicRange> {
+            Ok($self.dynamic_range)
+        }
+
         fn set_dynamic_range(&mut $self, value: &crate::ptp::fuji::DynamicRange) -> anyhow::Result<()> {
             $self.dynamic_range = *value;
             Ok(())
         }
     };
 
+    (@validate $self:ident, $model:ident, $values:ident, DynamicRange) => {
+        crate::features::simulation::values::check(
+            $model,
+            "Dynamic Range",
+            $values.dynamic_ranges,
+            &$self.dynamic_range,
+        )?;
+    };
+
+    (@recipe $self:ident, $recipe:ident, DynamicRange) => {
+        $recipe.dynamic_range = Some($self.dynamic_range);
+    };
+
     (@write $self:ident, $f:ident, DynamicRange) => {
         writeln!($f, "Dynamic Range: {}", $self.dynamic_range)?;
     };
 
     (@cap $self:ident, DynamicRangePriority) => {
+        fn get_dynamic_range_priority(&$self) -> anyhow::Result<crate::ptp::fuji::DynamicRangePriority> {
+            Ok($self.dynamic_range_priority)
+        }
+
         fn set_dynamic_range_priority(
             &mut $self,
             value: &crate::ptp::fuji::DynamicRangePriority
@@ -384,11 +674,28 @@ macro_rules! impl_simulation {
         }
     };
 
+    (@validate $self:ident, $model:ident, $values:ident, DynamicRangePriority) => {
+        crate::features::simulation::values::check(
+            $model,
+            "Dynamic Range Priority",
+            $values.dynamic_range_priorities,
+            &$self.dynamic_range_priority,
+        )?;
+    };
+
+    (@recipe $self:ident, $recipe:ident, DynamicRangePriority) => {
+        $recipe.dynamic_range_priority = Some($self.dynamic_range_priority);
+    };
+
     (@write $self:ident, $f:ident, DynamicRangePriority) => {
         writeln!($f, "Dynamic Range Priority: {}", $self.dynamic_range_priority)?;
     };
 
     (@cap $self:ident, LensModulationOptimizer) => {
+        fn get_lens_modulation_optimizer(&$self) -> anyhow::Result<crate::ptp::fuji::LensModulationOptimizer> {
+            Ok($self.lens_modulation_optimizer)
+        }
+
         fn set_lens_modulation_optimizer(
             &mut $self,
             value: &crate::ptp::fuji::LensModulationOptimizer
@@ -398,20 +705,34 @@ macro_rules! impl_simulation {
         }
     };
 
+    (@recipe $self:ident, $recipe:ident, LensModulationOptimizer) => {
+        $recipe.lens_modulation_optimizer = Some($self.lens_modulation_optimizer);
+    };
+
     (@write $self:ident, $f:ident, LensModulationOptimizer) => {
         writeln!($f, "Lens Modulation Optimizer: {}", $self.lens_modulation_optimizer)?;
     };
 
     (@cap $self:ident, ColorSpace) => {
+        fn get_color_space(&$self) -> anyhow::Result<crate::ptp::fuji::ColorSpace> {
+            Ok($self.color_space)
+        }
+
         fn set_color_space(&mut $self, value: &crate::ptp::fuji::ColorSpace) -> anyhow::Result<()> {
             $self.color_space = *value;
             Ok(())
         }
     };
 
+    (@recipe $self:ident, $recipe:ident, ColorSpace) => {
+        $recipe.color_space = Some($self.color_space);
+    };
+
     (@write $self:ident, $f:ident, ColorSpace) => {
         writeln!($f, "Color Space: {}", $self.color_space)?;
     };
+
+    (@validate $self:ident, $model:ident, $values:ident, $cap:ident) => {};
 }
 
 pub(crate) use impl_simulation;
@@ -1,6 +1,10 @@
-use crate::features::simulation::Simulation;
+use crate::features::simulation::{Simulation, SupportedValues};
 
 pub trait CameraSimulationParser {
+    fn supported_values(&self) -> &'static SupportedValues {
+        &SupportedValues::ANY
+    }
+
     fn deserialize_simulation(&self, simulation: &[u8]) -> anyhow::Result<Box<dyn Simulation>>;
 
     fn serialize_simulation(&self, simulation: &dyn Simulation) -> anyhow::Result<Vec<u8>>;
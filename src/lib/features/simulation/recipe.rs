@@ -0,0 +1,573 @@
+use std::{
+    any::Any,
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, bail};
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    features::{
+        render::ConversionProfile,
+        simulation::{
+            FieldGroup, RecipeLibrary, Simulation, SimulationFile, SimulationFormat,
+            SupportedValues, card, values,
+        },
+    },
+    input::{self, Choices},
+    ptp::fuji,
+};
+
+macro_rules! define_recipe {
+    ( $( $field:ident: $type:ty => $getter:ident, $setter:ident, $label:literal in $group:ident; )* ) => {
+        // A model-independent simulation. Unset fields are left untouched when applied.
+        #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        pub struct Recipe {
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub name: Option<fuji::CustomSettingName>,
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub size: Option<fuji::ImageSize>,
+            $(
+                #[serde(default, skip_serializing_if = "Option::is_none")]
+                pub $field: Option<$type>,
+            )*
+        }
+
+        impl Recipe {
+            pub fn apply_to_simulation(
+                &self,
+                target: &mut dyn Simulation,
+                model: &str,
+                values: &SupportedValues,
+            ) -> anyhow::Result<()> {
+                let recipe = self.adapt(model, values);
+
+                if let Some(name) = &recipe.name {
+                    target.set_name(name)?;
+                }
+
+                if let Some(size) = &recipe.size
+                    && target.set_size(size).is_err()
+                {
+                    warn!("{model} does not support Size, dropping it");
+                }
+
+                $(
+                    if let Some(value) = &recipe.$field
+                        && target.$setter(value).is_err()
+                    {
+                        warn!("{model} does not support {}, dropping it", $label);
+                    }
+                )*
+
+                Ok(())
+            }
+
+            pub fn apply_to_profile(
+                &self,
+                target: &mut dyn ConversionProfile,
+                model: &str,
+                values: &SupportedValues,
+            ) -> anyhow::Result<()> {
+                let recipe = self.adapt(model, values);
+
+                if let Some(size) = &recipe.size
+                    && target.set_size(size).is_err()
+                {
+                    warn!("{model} does not support Size, dropping it");
+                }
+
+                $(
+                    if let Some(value) = &recipe.$field
+                        && target.$setter(value).is_err()
+                    {
+                        warn!("{model} does not support {}, dropping it", $label);
+                    }
+                )*
+
+                Ok(())
+            }
+        }
+
+        impl Recipe {
+            // Sets every field of this recipe on `target` as is, failing on the first it does not
+            // support.
+            pub fn set_on_simulation(&self, target: &mut dyn Simulation) -> anyhow::Result<()> {
+                if let Some(name) = &self.name {
+                    target.set_name(name)?;
+                }
+                if let Some(size) = &self.size {
+                    target.set_size(size)?;
+                }
+                $(
+                    if let Some(value) = &self.$field {
+                        target.$setter(value)?;
+                    }
+                )*
+                Ok(())
+            }
+
+            pub fn set_on_profile(&self, target: &mut dyn ConversionProfile) -> anyhow::Result<()> {
+                if let Some(size) = &self.size {
+                    target.set_size(size)?;
+                }
+                $(
+                    if let Some(value) = &self.$field {
+                        target.$setter(value)?;
+                    }
+                )*
+                Ok(())
+            }
+        }
+
+        impl Recipe {
+            // Clears every field `keep` rejects, given its name and group.
+            pub fn retain(&mut self, keep: impl Fn(&str, Option<FieldGroup>) -> bool) {
+                if !keep("name", None) {
+                    self.name = None;
+                }
+                if !keep("size", Some(FieldGroup::Output)) {
+                    self.size = None;
+                }
+                $(
+                    if !keep(&field_key(stringify!($field)), Some(FieldGroup::$group)) {
+                        self.$field = None;
+                    }
+                )*
+            }
+        }
+
+        impl dyn Simulation {
+            // Field names accepted by `get_field` and `set_field`, matching the CLI options.
+            pub fn field_names() -> Vec<String> {
+                let mut names = vec![String::from("name"), String::from("size")];
+                $( names.push(field_key(stringify!($field))); )*
+                names
+            }
+
+            // Field names with their display labels, in card order.
+            pub fn field_labels() -> Vec<(String, &'static str)> {
+                let mut labels = vec![(String::from("name"), "Name"), (String::from("size"), "Size")];
+                $( labels.push((field_key(stringify!($field)), $label)); )*
+                labels
+            }
+
+            // Every value of a field the model supports, in order. Empty for free-form fields.
+            pub fn field_choices(field: &str, values: &SupportedValues) -> anyhow::Result<Vec<String>> {
+                let key = field_key(field);
+                match key.as_str() {
+                    "name" => return Ok(Vec::new()),
+                    "size" => return Ok(values.image_size_choices()),
+                    "quality" => return Ok(values::choices(values.image_qualities)),
+                    "simulation" => return Ok(values::choices(values.film_simulations)),
+                    "grain" => return Ok(values::choices(values.grain_effects)),
+                    "white-balance" => return Ok(values::choices(values.white_balances)),
+                    "dynamic-range" => return Ok(values::choices(values.dynamic_ranges)),
+                    "dynamic-range-priority" => {
+                        return Ok(values::choices(values.dynamic_range_priorities));
+                    }
+                    _ => {}
+                }
+                $(
+                    if key == field_key(stringify!($field)) {
+                        return Ok(<$type as Choices>::choices());
+                    }
+                )*
+                Err(unknown_field(field))
+            }
+
+            pub fn get_field(&self, field: &str) -> anyhow::Result<String> {
+                let key = field_key(field);
+                if key == "name" {
+                    return Ok(self.get_name()?.to_string());
+                }
+                if key == "size" {
+                    return Ok(fuji::CanonicalImageSize::from(self.get_size()?).to_string());
+                }
+                $(
+                    if key == field_key(stringify!($field)) {
+                        return Ok(self.$getter()?.to_string());
+                    }
+                )*
+                Err(unknown_field(field))
+            }
+
+            pub fn set_field(&mut self, field: &str, value: &str) -> anyhow::Result<()> {
+                let key = field_key(field);
+                if key == "name" {
+                    return self.set_name(&value.parse()?);
+                }
+                if key == "size" {
+                    let size: fuji::CanonicalImageSize = value.parse()?;
+                    return self.set_size(&fuji::ImageSize::from(size));
+                }
+                $(
+                    if key == field_key(stringify!($field)) {
+                        let value: $type = value
+                            .parse()
+                            .with_context(|| format!("Invalid {}", $label))?;
+                        return self.$setter(&value);
+                    }
+                )*
+                Err(unknown_field(field))
+            }
+        }
+
+        impl Recipe {
+            // Overrides this recipe with every field set in `other`.
+            pub fn merge(&mut self, other: &Self) {
+                if other.name.is_some() {
+                    self.name.clone_from(&other.name);
+                }
+                if other.size.is_some() {
+                    self.size = other.size;
+                }
+                $(
+                    if other.$field.is_some() {
+                        self.$field.clone_from(&other.$field);
+                    }
+                )*
+            }
+        }
+
+        impl Recipe {
+            // Every field with its display label, in card order.
+            pub fn fields(&self) -> Vec<(&'static str, Option<String>)> {
+                vec![
+                    ("Name", self.name.as_ref().map(ToString::to_string)),
+                    (
+                        "Size",
+                        self.size
+                            .map(|size| fuji::CanonicalImageSize::from(size).to_string()),
+                    ),
+                    $( ($label, self.$field.as_ref().map(ToString::to_string)), )*
+                ]
+            }
+        }
+
+        impl Simulation for Recipe {
+            fn as_any(&self) -> &dyn Any {
+                self
+            }
+
+            fn serialize(&self) -> anyhow::Result<Vec<u8>> {
+                Ok(serde_json::to_vec(self)?)
+            }
+
+            fn deserialize(data: &[u8]) -> anyhow::Result<Box<dyn Simulation>>
+            where
+                Self: Sized,
+            {
+                let recipe: Self = serde_json::from_slice(data)?;
+                Ok(Box::new(recipe))
+            }
+
+            fn to_recipe(&self) -> Recipe {
+                self.clone()
+            }
+
+            fn get_name(&self) -> anyhow::Result<fuji::CustomSettingName> {
+                self.name
+                    .clone()
+                    .ok_or_else(|| anyhow::anyhow!("This recipe has no name"))
+            }
+
+            fn set_name(&mut self, value: &fuji::CustomSettingName) -> anyhow::Result<()> {
+                self.name = Some(value.clone());
+                Ok(())
+            }
+
+            fn get_size(&self) -> anyhow::Result<fuji::ImageSize> {
+                self.size
+                    .ok_or_else(|| anyhow::anyhow!("This recipe does not set Size"))
+            }
+
+            fn set_size(&mut self, value: &fuji::ImageSize) -> anyhow::Result<()> {
+                self.size = Some(*value);
+                Ok(())
+            }
+
+            $(
+                fn $getter(&self) -> anyhow::Result<$type> {
+                    self.$field
+                        .clone()
+                        .ok_or_else(|| anyhow::anyhow!("This recipe does not set {}", $label))
+                }
+
+                fn $setter(&mut self, value: &$type) -> anyhow::Result<()> {
+                    self.$field = Some(value.clone());
+                    Ok(())
+                }
+            )*
+        }
+
+        impl fmt::Display for Recipe {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                if let Some(name) = &self.name {
+                    writeln!(f, "Name: {name}")?;
+                }
+                if let Some(size) = self.size {
+                    writeln!(f, "Size: {}", fuji::CanonicalImageSize::from(size))?;
+                }
+                $(
+                    if let Some(value) = &self.$field {
+                        writeln!(f, "{}: {value}", $label)?;
+                    }
+                )*
+                Ok(())
+            }
+        }
+    };
+}
+
+define_recipe! {
+    quality: fuji::ImageQuality => get_quality, set_quality, "Quality" in Output;
+    simulation: fuji::FilmSimulation => get_simulation, set_simulation, "Simulation" in Color;
+    monochromatic_color_temperature: fuji::MonochromaticColorShift
+        => get_monochromatic_color_temperature, set_monochromatic_color_temperature, "Monochromatic Color Temperature" in Color;
+    monochromatic_color_tint: fuji::MonochromaticColorShift
+        => get_monochromatic_color_tint, set_monochromatic_color_tint, "Monochromatic Color Tint" in Color;
+    highlight: fuji::HighlightTone => get_highlight, set_highlight, "Highlight" in Tone;
+    shadow: fuji::ShadowTone => get_shadow, set_shadow, "Shadow" in Tone;
+    color: fuji::Color => get_color, set_color, "Color" in Color;
+    sharpness: fuji::Sharpness => get_sharpness, set_sharpness, "Sharpness" in Effects;
+    clarity: fuji::Clarity => get_clarity, set_clarity, "Clarity" in Effects;
+    noise_reduction: fuji::NoiseReduction
+        => get_noise_reduction, set_noise_reduction, "Noise Reduction" in Effects;
+    grain: fuji::GrainEffect => get_grain, set_grain, "Grain" in Effects;
+    color_chrome_effect: fuji::ColorChromeEffect
+        => get_color_chrome_effect, set_color_chrome_effect, "Color Chrome Effect" in Color;
+    color_chrome_fx_blue: fuji::ColorChromeFXBlue
+        => get_color_chrome_fx_blue, set_color_chrome_fx_blue, "Color Chrome FX Blue" in Color;
+    smooth_skin_effect: fuji::SmoothSkinEffect
+        => get_smooth_skin_effect, set_smooth_skin_effect, "Smooth Skin Effect" in Effects;
+    white_balance: fuji::WhiteBalance
+        => get_white_balance, set_white_balance, "White Balance" in WhiteBalance;
+    white_balance_shift_red: fuji::WhiteBalanceShift
+        => get_white_balance_shift_red, set_white_balance_shift_red, "White Balance Shift Red" in WhiteBalance;
+    white_balance_shift_blue: fuji::WhiteBalanceShift
+        => get_white_balance_shift_blue, set_white_balance_shift_blue, "White Balance Shift Blue" in WhiteBalance;
+    white_balance_temperature: fuji::WhiteBalanceTemperature
+        => get_white_balance_temperature, set_white_balance_temperature, "White Balance Temperature" in WhiteBalance;
+    dynamic_range: fuji::DynamicRange
+        => get_dynamic_range, set_dynamic_range, "Dynamic Range" in Tone;
+    dynamic_range_priority: fuji::DynamicRangePriority
+        => get_dynamic_range_priority, set_dynamic_range_priority, "Dynamic Range Priority" in Tone;
+    lens_modulation_optimizer: fuji::LensModulationOptimizer
+        => get_lens_modulation_optimizer, set_lens_modulation_optimizer, "Lens Modulation Optimizer" in Effects;
+    color_space: fuji::ColorSpace => get_color_space, set_color_space, "Color Space" in Output;
+}
+
+// Accepts field names in any of snake_case, kebab-case or as their display label.
+fn field_key(field: &str) -> String {
+    field.trim().to_lowercase().replace(['_', ' '], "-")
+}
+
+fn unknown_field(field: &str) -> anyhow::Error {
+    let suggestion = input::closest(field, <dyn Simulation>::field_names())
+        .map(|name| format!(", did you mean '{name}'?"))
+        .unwrap_or_default();
+    anyhow::anyhow!("Unknown field '{field}'{suggestion}")
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeDifference {
+    pub field: &'static str,
+    pub left: Option<String>,
+    pub right: Option<String>,
+}
+
+impl Recipe {
+    // Recipes can `extends` another by path, relative to `base_dir`, or by library name.
+    pub fn from_bytes(
+        data: &[u8],
+        format: SimulationFormat,
+        base_dir: Option<&Path>,
+    ) -> anyhow::Result<Self> {
+        if format == SimulationFormat::Text {
+            let text = std::str::from_utf8(data).context("Recipe card is not valid UTF-8")?;
+            return card::parse_card(text);
+        }
+
+        Self::from_file(SimulationFile::parse(data, format)?, base_dir)
+    }
+
+    pub fn from_file(file: SimulationFile, base_dir: Option<&Path>) -> anyhow::Result<Self> {
+        Self::layer(file, base_dir, &mut Vec::new())
+    }
+
+    // `chain` holds the canonical paths of the bases loaded so far, to catch cycles.
+    fn layer(
+        mut file: SimulationFile,
+        base_dir: Option<&Path>,
+        chain: &mut Vec<PathBuf>,
+    ) -> anyhow::Result<Self> {
+        let extends = file.take_extends()?;
+        let recipe: Self = serde_json::from_value(file.simulation)
+            .context("Simulation file is not a valid recipe")?;
+
+        let Some(extends) = extends else {
+            return Ok(recipe);
+        };
+
+        let local = base_dir.unwrap_or(Path::new(".")).join(&extends);
+        let (path, base) = if local.is_file() {
+            let format = SimulationFormat::detect(None, Some(&local));
+            let data = fs::read(&local)?;
+            if format == SimulationFormat::Text {
+                // Recipe cards cannot extend anything, so they cannot close a cycle.
+                let mut base = Self::from_bytes(&data, format, None)
+                    .with_context(|| format!("Could not load base recipe {}", local.display()))?;
+                base.merge(&recipe);
+                return Ok(base);
+            }
+            (local, SimulationFile::parse(&data, format)?)
+        } else {
+            RecipeLibrary::open_default()?.get_file(&extends)?
+        };
+
+        let canonical = fs::canonicalize(&path)?;
+        if chain.contains(&canonical) {
+            bail!("Recipe '{extends}' extends itself");
+        }
+        chain.push(canonical);
+
+        let mut base = Self::layer(base, path.parent(), chain)
+            .with_context(|| format!("Could not load base recipe {}", path.display()))?;
+        base.merge(&recipe);
+        Ok(base)
+    }
+
+    // Clears the fields the camera ignores with this recipe's film simulation and white balance.
+    pub fn without_ignored(&self) -> Self {
+        let mut recipe = self.clone();
+
+        if let Some(simulation) = recipe.simulation {
+            if simulation.is_black_and_white() {
+                recipe.color = None;
+            } else {
+                recipe.monochromatic_color_temperature = None;
+                recipe.monochromatic_color_tint = None;
+            }
+        }
+
+        if recipe
+            .white_balance
+            .is_some_and(|white_balance| white_balance != fuji::WhiteBalance::Temperature)
+        {
+            recipe.white_balance_temperature = None;
+        }
+
+        recipe
+    }
+
+    pub fn diff(&self, other: &Self) -> Vec<RecipeDifference> {
+        self.fields()
+            .into_iter()
+            .zip(other.fields())
+            .filter(|((_, left), (_, right))| left != right)
+            .map(|((field, left), (_, right))| RecipeDifference { field, left, right })
+            .collect()
+    }
+
+    // Replaces values the model cannot represent with their closest supported alternative.
+    fn adapt(&self, model: &str, values: &SupportedValues) -> Self {
+        let mut recipe = self.clone();
+
+        // Cameras store HDR800+ as HDR800 with Dynamic Range Priority Plus. When that is not
+        // possible it is left as is, for the rules to reject.
+        if recipe.dynamic_range == Some(fuji::DynamicRange::HDR800Plus)
+            && values.supports_priority_plus()
+            && recipe.dynamic_range_priority.is_none_or(|priority| {
+                matches!(
+                    priority,
+                    fuji::DynamicRangePriority::Off | fuji::DynamicRangePriority::Plus
+                )
+            })
+        {
+            recipe.dynamic_range = Some(fuji::DynamicRange::HDR800);
+            recipe.dynamic_range_priority = Some(fuji::DynamicRangePriority::Plus);
+        }
+
+        recipe.size = adapt_value(
+            model,
+            "Size",
+            values.image_size_codes().as_deref(),
+            self.size,
+            fuji::ImageSize::fallback,
+        );
+        recipe.quality = adapt_value(
+            model,
+            "Quality",
+            values.image_qualities,
+            self.quality,
+            |_| None,
+        );
+        recipe.simulation = adapt_value(
+            model,
+            "Simulation",
+            values.film_simulations,
+            self.simulation,
+            fuji::FilmSimulation::fallback,
+        );
+        recipe.grain = adapt_value(model, "Grain", values.grain_effects, self.grain, |_| None);
+        recipe.white_balance = adapt_value(
+            model,
+            "White Balance",
+            values.white_balances,
+            self.white_balance,
+            |_| None,
+        );
+        recipe.dynamic_range = adapt_value(
+            model,
+            "Dynamic Range",
+            values.dynamic_ranges,
+            recipe.dynamic_range,
+            |_| None,
+        );
+        recipe.dynamic_range_priority = adapt_value(
+            model,
+            "Dynamic Range Priority",
+            values.dynamic_range_priorities,
+            recipe.dynamic_range_priority,
+            |_| None,
+        );
+
+        recipe
+    }
+}
+
+fn adapt_value<T>(
+    model: &str,
+    label: &str,
+    supported: Option<&[T]>,
+    value: Option<T>,
+    fallback: impl Fn(T) -> Option<T>,
+) -> Option<T>
+where
+    T: Copy + PartialEq + fmt::Display,
+{
+    let (Some(supported), Some(original)) = (supported, value) else {
+        return value;
+    };
+
+    let mut current = original;
+    loop {
+        if supported.contains(&current) {
+            if current != original {
+                warn!("{model} does not support {label} '{original}', using '{current}' instead");
+            }
+            return Some(current);
+        }
+
+        let Some(next) = fallback(current) else {
+            warn!("{model} does not support {label} '{original}', dropping it");
+            return None;
+        };
+        current = next;
+    }
+}
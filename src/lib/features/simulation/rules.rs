@@ -0,0 +1,218 @@
+use std::fmt;
+
+use anyhow::bail;
+use log::warn;
+use serde::Serialize;
+
+use crate::{
+    features::simulation::{Recipe, SupportedValues},
+    ptp::fuji,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleViolation {
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl fmt::Display for RuleViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+struct Rule {
+    // Fields, by label, that make the rule worth reporting when they change.
+    triggers: &'static [&'static str],
+    check: fn(&Recipe, &str, &SupportedValues) -> Option<RuleViolation>,
+}
+
+const fn warning(message: String) -> Option<RuleViolation> {
+    Some(RuleViolation {
+        severity: Severity::Warning,
+        message,
+    })
+}
+
+const fn error(message: String) -> Option<RuleViolation> {
+    Some(RuleViolation {
+        severity: Severity::Error,
+        message,
+    })
+}
+
+fn is_zero(value: Option<i16>) -> bool {
+    value.is_none_or(|value| value == 0)
+}
+
+static RULES: &[Rule] = &[
+    Rule {
+        triggers: &[
+            "Simulation",
+            "Monochromatic Color Temperature",
+            "Monochromatic Color Tint",
+        ],
+        check: |recipe, _, _| {
+            let simulation = recipe.simulation?;
+            if simulation.is_black_and_white()
+                || is_zero(recipe.monochromatic_color_temperature.map(|v| v.to_int()))
+                    && is_zero(recipe.monochromatic_color_tint.map(|v| v.to_int()))
+            {
+                return None;
+            }
+            warning(format!(
+                "Monochromatic color shifts only apply to monochrome film simulations, {simulation} ignores them"
+            ))
+        },
+    },
+    Rule {
+        triggers: &["Simulation", "Color"],
+        check: |recipe, _, _| {
+            let simulation = recipe.simulation?;
+            if !simulation.is_black_and_white() || is_zero(recipe.color.map(|v| v.to_int())) {
+                return None;
+            }
+            warning(format!("Color has no effect on {simulation}"))
+        },
+    },
+    Rule {
+        triggers: &["Simulation", "Color Chrome FX Blue"],
+        check: |recipe, _, _| {
+            let simulation = recipe.simulation?;
+            if !simulation.is_black_and_white()
+                || recipe.color_chrome_fx_blue? == fuji::ColorChromeFXBlue::Off
+            {
+                return None;
+            }
+            warning(format!(
+                "Color Chrome FX Blue has no effect on {simulation}"
+            ))
+        },
+    },
+    Rule {
+        triggers: &["White Balance Temperature"],
+        check: |recipe, _, _| {
+            let white_balance = recipe.white_balance?;
+            recipe.white_balance_temperature?;
+            if white_balance == fuji::WhiteBalance::Temperature {
+                return None;
+            }
+            warning(format!(
+                "White Balance Temperature only applies to the Temperature White Balance, {white_balance} ignores it"
+            ))
+        },
+    },
+    Rule {
+        triggers: &[
+            "White Balance",
+            "White Balance Shift Red",
+            "White Balance Shift Blue",
+        ],
+        check: |recipe, _, _| {
+            if recipe.white_balance? != fuji::WhiteBalance::AsShot
+                || is_zero(recipe.white_balance_shift_red.map(|v| v.to_int()))
+                    && is_zero(recipe.white_balance_shift_blue.map(|v| v.to_int()))
+            {
+                return None;
+            }
+            warning(String::from(
+                "White Balance has been shifted but no explicit White Balance mode has been set. Consider setting a White Balance mode.",
+            ))
+        },
+    },
+    Rule {
+        triggers: &["Dynamic Range", "Dynamic Range Priority"],
+        check: |recipe, model, values| {
+            let dynamic_range = recipe.dynamic_range?;
+            // Recipe adaptation turns HDR800+ into HDR800 with priority Plus whenever it can.
+            if dynamic_range == fuji::DynamicRange::HDR800Plus {
+                if !values.supports_priority_plus() {
+                    return error(format!(
+                        "HDR800+ requires Dynamic Range Priority Plus, which the {model} does not support"
+                    ));
+                }
+                if let Some(priority) = recipe.dynamic_range_priority {
+                    return error(format!(
+                        "HDR800+ cannot be combined with Dynamic Range Priority {priority}"
+                    ));
+                }
+            }
+
+            let priority = recipe.dynamic_range_priority?;
+            match priority {
+                fuji::DynamicRangePriority::Off | fuji::DynamicRangePriority::Plus => None,
+                _ if dynamic_range == fuji::DynamicRange::Auto => None,
+                fuji::DynamicRangePriority::Weak | fuji::DynamicRangePriority::Strong => {
+                    error(format!(
+                        "Dynamic Range Priority {priority} cannot be combined with Dynamic Range {dynamic_range}, set Dynamic Range to Auto"
+                    ))
+                }
+                fuji::DynamicRangePriority::Auto => warning(format!(
+                    "Dynamic Range Priority {priority} overrides Dynamic Range {dynamic_range}"
+                )),
+            }
+        },
+    },
+    Rule {
+        triggers: &["Dynamic Range"],
+        check: |recipe, model, values| {
+            let base_iso = values.base_iso?;
+            let factor = match recipe.dynamic_range? {
+                fuji::DynamicRange::HDR200 => 2,
+                fuji::DynamicRange::HDR400 => 4,
+                _ => return None,
+            };
+            warning(format!(
+                "{} requires ISO {} or higher on the {model}",
+                recipe.dynamic_range?,
+                base_iso * factor
+            ))
+        },
+    },
+];
+
+// The rules `after` breaks that are triggered by a field changed since `before`, so settings
+// already on the camera are not reported on every write.
+pub fn check_changes(
+    before: &Recipe,
+    after: &Recipe,
+    model: &str,
+    values: &SupportedValues,
+) -> Vec<RuleViolation> {
+    let changed: Vec<&str> = before
+        .diff(after)
+        .into_iter()
+        .map(|difference| difference.field)
+        .collect();
+
+    RULES
+        .iter()
+        .filter(|rule| rule.triggers.iter().any(|field| changed.contains(field)))
+        .filter_map(|rule| (rule.check)(after, model, values))
+        .collect()
+}
+
+// Logs warnings and fails if any rule is broken with an error.
+pub fn enforce(violations: &[RuleViolation]) -> anyhow::Result<()> {
+    let mut errors = Vec::new();
+    for violation in violations {
+        match violation.severity {
+            Severity::Warning => warn!("{violation}"),
+            Severity::Error => errors.push(violation.to_string()),
+        }
+    }
+
+    if !errors.is_empty() {
+        bail!("{}", errors.join("\n"));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,162 @@
+use std::{fmt::Display, str::FromStr};
+
+use anyhow::{anyhow, bail};
+
+use crate::{
+    input::{Choices, closest},
+    ptp::fuji,
+};
+
+// A size code the model supports, with the pixel dimensions its sensor produces for it.
+#[derive(Debug, Clone, Copy)]
+pub struct ModelImageSize {
+    pub size: fuji::ImageSize,
+    pub width: u32,
+    pub height: u32,
+}
+
+// `None` means the camera accepts every value of the enum.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SupportedValues {
+    pub image_sizes: Option<&'static [ModelImageSize]>,
+    pub image_qualities: Option<&'static [fuji::ImageQuality]>,
+    pub film_simulations: Option<&'static [fuji::FilmSimulation]>,
+    pub grain_effects: Option<&'static [fuji::GrainEffect]>,
+    pub white_balances: Option<&'static [fuji::WhiteBalance]>,
+    pub dynamic_ranges: Option<&'static [fuji::DynamicRange]>,
+    pub dynamic_range_priorities: Option<&'static [fuji::DynamicRangePriority]>,
+    pub base_iso: Option<u16>,
+}
+
+impl SupportedValues {
+    pub const ANY: Self = Self {
+        image_sizes: None,
+        image_qualities: None,
+        film_simulations: None,
+        grain_effects: None,
+        white_balances: None,
+        dynamic_ranges: None,
+        dynamic_range_priorities: None,
+        base_iso: None,
+    };
+
+    // HDR800+ can only be stored where Dynamic Range Priority Plus exists.
+    pub fn supports_priority_plus(&self) -> bool {
+        self.dynamic_range_priorities
+            .is_none_or(|priorities| priorities.contains(&fuji::DynamicRangePriority::Plus))
+    }
+
+    pub fn image_size_codes(&self) -> Option<Vec<fuji::ImageSize>> {
+        self.image_sizes
+            .map(|sizes| sizes.iter().map(|entry| entry.size).collect())
+    }
+
+    // Resolves a canonical size to the code the model uses for it.
+    pub fn image_size(
+        &self,
+        model: &str,
+        size: fuji::CanonicalImageSize,
+    ) -> anyhow::Result<fuji::ImageSize> {
+        let supported = self.image_sizes.map(|sizes| {
+            sizes
+                .iter()
+                .map(|entry| fuji::CanonicalImageSize::from(entry.size))
+                .collect::<Vec<_>>()
+        });
+        check(model, "Size", supported.as_deref(), &size)?;
+        Ok(size.into())
+    }
+
+    // Accepts a canonical size, or pixel dimensions as produced by this model.
+    pub fn parse_image_size(&self, model: &str, input: &str) -> anyhow::Result<fuji::ImageSize> {
+        if let Some((width, height)) = input.trim().split_once(['x', 'X'])
+            && let (Ok(width), Ok(height)) = (width.trim().parse(), height.trim().parse())
+        {
+            return self
+                .image_size_from_dimensions(width, height)
+                .ok_or_else(|| unknown(model, "Size", self.image_size_choices(), input));
+        }
+
+        let Ok(size) = input.parse::<fuji::CanonicalImageSize>() else {
+            return Err(unknown(model, "Size", self.image_size_choices(), input));
+        };
+        self.image_size(model, size)
+    }
+
+    pub fn image_size_choices(&self) -> Vec<String> {
+        match self.image_sizes {
+            Some(sizes) => sizes
+                .iter()
+                .map(|entry| fuji::CanonicalImageSize::from(entry.size).to_string())
+                .collect(),
+            None => fuji::CanonicalImageSize::iter()
+                .map(|size| size.to_string())
+                .collect(),
+        }
+    }
+
+    pub fn image_size_from_dimensions(&self, width: u32, height: u32) -> Option<fuji::ImageSize> {
+        self.image_sizes?
+            .iter()
+            .find(|entry| entry.width == width && entry.height == height)
+            .map(|entry| entry.size)
+    }
+}
+
+pub fn check<T>(model: &str, label: &str, supported: Option<&[T]>, value: &T) -> anyhow::Result<()>
+where
+    T: PartialEq + Display,
+{
+    let Some(supported) = supported else {
+        return Ok(());
+    };
+
+    if supported.contains(value) {
+        return Ok(());
+    }
+
+    let choices = supported.iter().map(ToString::to_string);
+    if let Some(best) = closest(&value.to_string(), choices.clone()) {
+        bail!("{model} does not support {label} '{value}'. Did you mean '{best}'?");
+    }
+
+    let choices = choices.collect::<Vec<_>>().join(", ");
+    bail!("{model} does not support {label} '{value}'. Supported values: {choices}");
+}
+
+// Parses a value like its `FromStr`, but only accepts and suggests the values the model supports.
+pub fn parse<T>(model: &str, label: &str, supported: Option<&[T]>, input: &str) -> anyhow::Result<T>
+where
+    T: FromStr<Err = anyhow::Error> + PartialEq + Display + Choices,
+{
+    let Some(supported) = supported else {
+        return input.parse();
+    };
+
+    let Ok(value) = input.parse() else {
+        let choices = supported.iter().map(ToString::to_string).collect();
+        return Err(unknown(model, label, choices, input));
+    };
+    check(model, label, Some(supported), &value)?;
+    Ok(value)
+}
+
+// The values of a field the model supports, as accepted by `parse`.
+pub fn choices<T>(supported: Option<&[T]>) -> Vec<String>
+where
+    T: Display + Choices,
+{
+    supported.map_or_else(T::choices, |supported| {
+        supported.iter().map(ToString::to_string).collect()
+    })
+}
+
+fn unknown(model: &str, label: &str, choices: Vec<String>, input: &str) -> anyhow::Error {
+    if let Some(best) = closest(input, choices.clone()) {
+        return anyhow!("Unknown {label} '{input}' for {model}. Did you mean '{best}'?");
+    }
+    anyhow!(
+        "Unknown {label} '{input}' for {model}. Supported values: {}",
+        choices.join(", ")
+    )
+}
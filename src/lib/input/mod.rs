@@ -20,28 +20,35 @@ impl<T: AsRef<str>> CleanAlphanumeric for T {
 
 const SIMILARITY_THRESHOLD: usize = 8;
 
-pub trait Choices {
-    fn choices() -> Vec<String>;
-
-    fn closest(input: &str) -> Option<String> {
-        let input_lower = input.to_lowercase();
+pub fn closest<I>(input: &str, choices: I) -> Option<String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let input_lower = input.to_lowercase();
 
-        let mut best_score = usize::MAX;
-        let mut best_match: Option<String> = None;
+    let mut best_score = usize::MAX;
+    let mut best_match: Option<String> = None;
 
-        for choice in Self::choices() {
-            let dist = damerau_levenshtein(&input_lower, &choice.to_lowercase());
-            if dist < best_score {
-                best_score = dist;
-                best_match = Some(choice.clone());
-            }
+    for choice in choices {
+        let dist = damerau_levenshtein(&input_lower, &choice.to_lowercase());
+        if dist < best_score {
+            best_score = dist;
+            best_match = Some(choice);
         }
+    }
 
-        if best_score <= SIMILARITY_THRESHOLD {
-            best_match
-        } else {
-            None
-        }
+    if best_score <= SIMILARITY_THRESHOLD {
+        best_match
+    } else {
+        None
+    }
+}
+
+pub trait Choices {
+    fn choices() -> Vec<String>;
+
+    fn closest(input: &str) -> Option<String> {
+        closest(input, Self::choices())
     }
 }
 
@@ -3,14 +3,20 @@ pub mod features;
 pub mod input;
 pub mod ptp;
 
-use anyhow::{anyhow, bail};
+use std::path::Path;
+
+use anyhow::{Context, anyhow, bail};
 use devices::x_trans_v;
 use features::{
     base::{CameraBase, info::CameraInfo},
-    simulation::Simulation,
+    simulation::{
+        BundleSlot, Recipe, Simulation, SimulationBundle, SimulationFile, SimulationFormat,
+        SlotDrift, SupportedValues, card, envelope::SimulationFileHeader,
+    },
 };
-use log::{debug, error};
+use log::{debug, error, warn};
 use ptp::{Ptp, fuji};
+use ptp_cursor::DecodeLimits;
 use rusb::{GlobalContext, constants::LIBUSB_CLASS_IMAGE};
 
 use crate::{
@@ -126,6 +132,8 @@ impl Camera {
             handle,
             transaction_id,
             chunk_size,
+            limits: DecodeLimits::default(),
+            dry_run: false,
         };
 
         ptp.open_session(SESSION)?;
@@ -207,6 +215,15 @@ impl Camera {
         self.r#impl.camera_definition().product
     }
 
+    // Performs reads as usual but only logs the writes that would be made.
+    pub const fn set_dry_run(&mut self, dry_run: bool) {
+        self.ptp.dry_run = dry_run;
+    }
+
+    pub const fn is_dry_run(&self) -> bool {
+        self.ptp.dry_run
+    }
+
     pub fn connected_usb_id(&self) -> String {
         format!("{}.{}", self.ptp.bus, self.ptp.address)
     }
@@ -231,17 +248,156 @@ impl Camera {
         }
     }
 
-    pub fn serialize_simulation(&self, simulation: &dyn Simulation) -> anyhow::Result<Vec<u8>> {
+    pub fn serialize_simulation(
+        &mut self,
+        simulation: &dyn Simulation,
+        format: SimulationFormat,
+    ) -> anyhow::Result<Vec<u8>> {
+        if format == SimulationFormat::Text {
+            return Ok(card::write_card(&simulation.to_recipe())?.into_bytes());
+        }
+
+        let body = if let Some(simulations) = self.r#impl.as_simulation_parser() {
+            simulations.serialize_simulation(simulation)?
+        } else {
+            bail!(ERROR_CAMERA_DOES_NOT_SUPPORT_SIMULATION_PARSING);
+        };
+
+        SimulationFile::new(self.name(), self.firmware(), &body)?.to_vec(format)
+    }
+
+    pub fn export_simulations(&mut self, format: SimulationFormat) -> anyhow::Result<Vec<u8>> {
+        let simulations = self
+            .custom_settings_slots()?
+            .into_iter()
+            .map(|slot| Ok((slot, self.get_simulation(slot)?)))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let Some(parser) = self.r#impl.as_simulation_parser() else {
+            bail!(ERROR_CAMERA_DOES_NOT_SUPPORT_SIMULATION_PARSING);
+        };
+
+        let slots = simulations
+            .into_iter()
+            .map(|(slot, simulation)| {
+                let body = parser.serialize_simulation(&*simulation)?;
+                Ok(BundleSlot {
+                    slot,
+                    name: simulation.get_name()?,
+                    simulation: serde_json::from_slice(&body)?,
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        SimulationBundle {
+            header: SimulationFileHeader::new(self.name(), self.firmware()),
+            slots,
+        }
+        .to_vec(format)
+    }
+
+    // Writes the selected slots of a bundle (all of them if `only` is empty), restoring every
+    // slot if any write fails. Returns the slots that changed.
+    pub fn import_simulations(
+        &mut self,
+        bundle: &[u8],
+        format: SimulationFormat,
+        only: &[fuji::CustomSetting],
+    ) -> anyhow::Result<Vec<SlotDrift>> {
+        let bundle = SimulationBundle::parse(bundle, format)?;
+
+        for slot in only {
+            if !bundle.slots.iter().any(|entry| entry.slot == *slot) {
+                bail!("Slot {slot} is not in the bundle");
+            }
+        }
+
+        let convert = match bundle.header.check_model(self.name()) {
+            Ok(()) => false,
+            Err(e) => {
+                warn!("{e}. Converting it, unsupported settings will be adjusted or dropped.");
+                true
+            }
+        };
+
+        let Some(parser) = self.r#impl.as_simulation_parser() else {
+            bail!(ERROR_CAMERA_DOES_NOT_SUPPORT_SIMULATION_PARSING);
+        };
+
+        let desired = bundle
+            .slots
+            .iter()
+            .filter(|entry| only.is_empty() || only.contains(&entry.slot))
+            .map(|entry| {
+                let recipe = if convert {
+                    serde_json::from_value(entry.simulation.clone())?
+                } else {
+                    parser
+                        .deserialize_simulation(&serde_json::to_vec(&entry.simulation)?)?
+                        .to_recipe()
+                };
+                Ok((entry.slot, recipe))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()
+            .context("Simulation bundle contains an invalid slot")?;
+
+        let plan = self.plan_simulation_sync(&desired)?;
+        self.apply_simulation_sync(&plan)?;
+
+        Ok(plan)
+    }
+
+    fn firmware(&mut self) -> Option<String> {
+        match self.ptp.get_info() {
+            Ok(info) => Some(info.device_version),
+            Err(e) => {
+                debug!("Could not read firmware version: {e}");
+                None
+            }
+        }
+    }
+
+    // `base_dir` is where recipes the simulation `extends` are looked up.
+    pub fn deserialize_simulation(
+        &self,
+        simulation: &[u8],
+        format: SimulationFormat,
+        base_dir: Option<&Path>,
+    ) -> anyhow::Result<Box<dyn Simulation>> {
+        if format == SimulationFormat::Text {
+            return Ok(Box::new(Recipe::from_bytes(simulation, format, base_dir)?));
+        }
+
+        let file = SimulationFile::parse(simulation, format)?;
+        let model_check = file.check_model(self.name());
+        if let Err(e) = &model_check {
+            warn!("{e}. Converting it, unsupported settings will be adjusted or dropped.");
+        }
+
+        if file.simulation.get("extends").is_some() {
+            return Ok(Box::new(Recipe::from_file(file, base_dir)?));
+        }
+
+        if model_check.is_err() {
+            let recipe: Recipe = serde_json::from_value(file.simulation)
+                .context("Simulation file is not a valid recipe")?;
+            return Ok(Box::new(recipe));
+        }
+
         if let Some(simulations) = self.r#impl.as_simulation_parser() {
-            simulations.serialize_simulation(simulation)
+            simulations
+                .deserialize_simulation(&file.simulation_bytes()?)
+                .with_context(|| {
+                    format!("Simulation file is not a valid {} simulation", self.name())
+                })
         } else {
             bail!(ERROR_CAMERA_DOES_NOT_SUPPORT_SIMULATION_PARSING);
         }
     }
 
-    pub fn deserialize_simulation(&self, simulation: &[u8]) -> anyhow::Result<Box<dyn Simulation>> {
+    pub fn supported_values(&self) -> anyhow::Result<&'static SupportedValues> {
         if let Some(simulations) = self.r#impl.as_simulation_parser() {
-            simulations.deserialize_simulation(simulation)
+            Ok(simulations.supported_values())
         } else {
             bail!(ERROR_CAMERA_DOES_NOT_SUPPORT_SIMULATION_PARSING);
         }
@@ -255,6 +411,14 @@ impl Camera {
         }
     }
 
+    pub fn find_slot(&mut self, name: &str) -> anyhow::Result<fuji::CustomSetting> {
+        if let Some(sim) = self.r#impl.as_simulation_manager() {
+            sim.find_slot(&mut self.ptp, name)
+        } else {
+            bail!(ERROR_CAMERA_DOES_NOT_SUPPORT_SIMULATION_MANAGEMENT);
+        }
+    }
+
     pub fn get_simulation(
         &mut self,
         slot: fuji::CustomSetting,
@@ -290,6 +454,80 @@ impl Camera {
         }
     }
 
+    pub fn copy_simulation(
+        &mut self,
+        from: fuji::CustomSetting,
+        to: fuji::CustomSetting,
+    ) -> anyhow::Result<()> {
+        if let Some(sim) = self.r#impl.as_simulation_manager() {
+            sim.copy_simulation(&mut self.ptp, from, to)
+        } else {
+            bail!(ERROR_CAMERA_DOES_NOT_SUPPORT_SIMULATION_MANAGEMENT);
+        }
+    }
+
+    pub fn swap_simulations(
+        &mut self,
+        a: fuji::CustomSetting,
+        b: fuji::CustomSetting,
+    ) -> anyhow::Result<()> {
+        if let Some(sim) = self.r#impl.as_simulation_manager() {
+            sim.swap_simulations(&mut self.ptp, a, b)
+        } else {
+            bail!(ERROR_CAMERA_DOES_NOT_SUPPORT_SIMULATION_MANAGEMENT);
+        }
+    }
+
+    pub fn move_simulation(
+        &mut self,
+        from: fuji::CustomSetting,
+        to: fuji::CustomSetting,
+    ) -> anyhow::Result<()> {
+        if let Some(sim) = self.r#impl.as_simulation_manager() {
+            sim.move_simulation(&mut self.ptp, from, to)
+        } else {
+            bail!(ERROR_CAMERA_DOES_NOT_SUPPORT_SIMULATION_MANAGEMENT);
+        }
+    }
+
+    pub fn default_simulation(
+        &mut self,
+        slot: fuji::CustomSetting,
+    ) -> anyhow::Result<Box<dyn Simulation>> {
+        if let Some(sim) = self.r#impl.as_simulation_manager() {
+            sim.default_simulation(&mut self.ptp, slot)
+        } else {
+            bail!(ERROR_CAMERA_DOES_NOT_SUPPORT_SIMULATION_MANAGEMENT);
+        }
+    }
+
+    pub fn reset_simulation(&mut self, slot: fuji::CustomSetting) -> anyhow::Result<()> {
+        if let Some(sim) = self.r#impl.as_simulation_manager() {
+            sim.reset_simulation(&mut self.ptp, slot)
+        } else {
+            bail!(ERROR_CAMERA_DOES_NOT_SUPPORT_SIMULATION_MANAGEMENT);
+        }
+    }
+
+    pub fn plan_simulation_sync(
+        &mut self,
+        desired: &[(fuji::CustomSetting, Recipe)],
+    ) -> anyhow::Result<Vec<SlotDrift>> {
+        if let Some(sim) = self.r#impl.as_simulation_manager() {
+            sim.plan_sync(&mut self.ptp, desired)
+        } else {
+            bail!(ERROR_CAMERA_DOES_NOT_SUPPORT_SIMULATION_MANAGEMENT);
+        }
+    }
+
+    pub fn apply_simulation_sync(&mut self, plan: &[SlotDrift]) -> anyhow::Result<()> {
+        if let Some(sim) = self.r#impl.as_simulation_manager() {
+            sim.apply_sync(&mut self.ptp, plan)
+        } else {
+            bail!(ERROR_CAMERA_DOES_NOT_SUPPORT_SIMULATION_MANAGEMENT);
+        }
+    }
+
     pub fn render(
         &mut self,
         image: &[u8],
@@ -299,6 +537,9 @@ impl Camera {
         draft: bool,
     ) -> anyhow::Result<Vec<u8>> {
         if let Some(renders) = self.r#impl.as_render_manager() {
+            if self.ptp.dry_run {
+                return renders.dry_run_render(conversion_profile_modifier);
+            }
             renders.render(&mut self.ptp, image, conversion_profile_modifier, draft)
         } else {
             bail!(ERROR_CAMERA_DOES_NOT_SUPPORT_RENDER_MANAGEMENT);
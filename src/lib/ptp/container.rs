@@ -47,16 +47,35 @@ pub enum CommandCode {
     GetDeviceInfo = 0x1001,
     OpenSession = 0x1002,
     CloseSession = 0x1003,
+    GetStorageIDs = 0x1004,
+    GetStorageInfo = 0x1005,
     GetObjectHandles = 0x1007,
     GetObjectInfo = 0x1008,
     GetObject = 0x1009,
     DeleteObject = 0x100B,
     SendObjectInfo = 0x100C,
     SendObject = 0x100D,
+    GetDevicePropDesc = 0x1014,
     GetDevicePropValue = 0x1015,
     SetDevicePropValue = 0x1016,
     FujiSendObjectInfo = 0x900c,
     FujiSendObject = 0x900d,
+    GetObjectPropDesc = 0x9802,
+}
+
+impl CommandCode {
+    // Commands that change state on the camera, skipped in dry runs.
+    pub const fn is_write(self) -> bool {
+        matches!(
+            self,
+            Self::DeleteObject
+                | Self::SendObjectInfo
+                | Self::SendObject
+                | Self::SetDevicePropValue
+                | Self::FujiSendObjectInfo
+                | Self::FujiSendObject
+        )
+    }
 }
 
 #[repr(u16)]
@@ -131,12 +150,7 @@ impl TryFrom<u16> for ContainerCode {
 }
 
 impl PtpSerialize for ContainerCode {
-    fn try_into_ptp(&self) -> io::Result<Vec<u8>> {
-        let value: u16 = (*self).into();
-        value.try_into_ptp()
-    }
-
-    fn try_write_ptp(&self, buf: &mut Vec<u8>) -> io::Result<()> {
+    fn try_write_ptp<W: ptp_cursor::Write + ?Sized>(&self, buf: &mut W) -> io::Result<()> {
         let value: u16 = (*self).into();
         value.try_write_ptp(buf)
     }
@@ -45,8 +45,9 @@ impl From<io::Error> for Error {
     fn from(e: io::Error) -> Self {
         match e.kind() {
             io::ErrorKind::UnexpectedEof => {
-                Self::Malformed("Unexpected end of message".to_string())
+                Self::Malformed(format!("Unexpected end of message: {e}"))
             }
+            io::ErrorKind::InvalidData => Self::Malformed(e.to_string()),
             _ => Self::Io(e),
         }
     }
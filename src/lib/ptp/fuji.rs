@@ -1,6 +1,5 @@
 use std::{
-    fmt,
-    io::{self, Write},
+    fmt, io,
     ops::{Deref, DerefMut},
     str::FromStr,
 };
@@ -12,6 +11,7 @@ use ptp_macro::{PtpDeserialize, PtpSerialize};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use serde_with::{DeserializeFromStr, SerializeDisplay};
+use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
 use crate::{
@@ -109,6 +109,16 @@ macro_rules! fuji_i16 {
             }
         }
 
+        impl crate::input::Choices for $name {
+            #[allow(clippy::cast_sign_loss)]
+            fn choices() -> Vec<String> {
+                (Self::RAW_MIN..=Self::RAW_MAX)
+                    .step_by(Self::RAW_STEP as usize)
+                    .map(|raw| Self(raw).to_string())
+                    .collect()
+            }
+        }
+
         impl std::fmt::Display for $name {
             fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
                 write!(f, "{}", self.to_int())
@@ -229,6 +239,16 @@ macro_rules! fuji_i16_frac {
             }
         }
 
+        impl crate::input::Choices for $name {
+            #[allow(clippy::cast_sign_loss)]
+            fn choices() -> Vec<String> {
+                (Self::RAW_MIN..=Self::RAW_MAX)
+                    .step_by(Self::RAW_STEP as usize)
+                    .map(|raw| Self(raw).to_string())
+                    .collect()
+            }
+        }
+
         impl std::fmt::Display for $name {
             fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
                 write!(f, "{}", self.to_float())
@@ -386,6 +406,12 @@ macro_rules! fuji_enum {
             type Err = anyhow::Error;
 
             fn from_str(s: &str) -> anyhow::Result<Self> {
+                // Exact display names first, so that e.g. "HDR800+" survives a round-trip.
+                match s {
+                    $($display_string => return Ok(Self::$variant_name),)*
+                    _ => {}
+                }
+
                 match s.clean().as_str() {
                     $($($match_string)|* => return Ok(Self::$variant_name),)*
                     _ => {}
@@ -446,25 +472,252 @@ fuji_enum! {
     }
 }
 
+// Size codes name an aspect ratio and size class. The pixel dimensions they produce depend on the
+// sensor and are declared per model in `SupportedValues::image_sizes`. The pixel aliases are the
+// 40MP labels older simulation files used for every model.
 fuji_enum! {
-    #[derive(SerializeDisplay, DeserializeFromStr, Default)]
+    #[derive(Default)]
     ImageSize, {
         #[default]
-        R7728x5152 = 0x7, "7728x5152", ["7728x5152"],
-        R7728x4344 = 0x8, "7728x4344", ["7728x4344"],
-        R5152x5152 = 0x9, "5152x5152", ["5152x5152"],
-        R6864x5152 = 0xe, "6864x5152", ["6864x5152"],
-        R6432x5152 = 0x10, "6432x5152", ["6432x5152"],
-        R5472x3648 = 0x4, "5472x3648", ["5472x3648"],
-        R5472x3080 = 0x5, "5472x3080", ["5472x3080"],
-        R3648x3648 = 0x6, "3648x3648", ["3648x3648"],
-        R4864x3648 = 0x12, "4864x3648", ["4864x3648"],
-        R4560x3648 = 0x14, "4560x3648", ["4560x3648"],
-        R3888x2592 = 0x1, "3888x2592", ["3888x2592"],
-        R3888x2184 = 0x2, "3888x2184", ["3888x2184"],
-        R2592x2592 = 0x3, "2592x2592", ["2592x2592"],
-        R3456x2592 = 0xa, "3456x2592", ["3456x2592"],
-        R3264x2592 = 0xc, "3264x2592", ["3264x2592"],
+        L3x2 = 0x7, "3:2-L", ["32-l", "32l", "l-32", "l32", "7728x5152"],
+        L16x9 = 0x8, "16:9-L", ["169-l", "169l", "l-169", "l169", "7728x4344"],
+        L1x1 = 0x9, "1:1-L", ["11-l", "11l", "l-11", "l11", "5152x5152"],
+        L4x3 = 0xe, "4:3-L", ["43-l", "43l", "l-43", "l43", "6864x5152"],
+        L5x4 = 0x10, "5:4-L", ["54-l", "54l", "l-54", "l54", "6432x5152"],
+        M3x2 = 0x4, "3:2-M", ["32-m", "32m", "m-32", "m32", "5472x3648"],
+        M16x9 = 0x5, "16:9-M", ["169-m", "169m", "m-169", "m169", "5472x3080"],
+        M1x1 = 0x6, "1:1-M", ["11-m", "11m", "m-11", "m11", "3648x3648"],
+        M4x3 = 0x12, "4:3-M", ["43-m", "43m", "m-43", "m43", "4864x3648"],
+        M5x4 = 0x14, "5:4-M", ["54-m", "54m", "m-54", "m54", "4560x3648"],
+        S3x2 = 0x1, "3:2-S", ["32-s", "32s", "s-32", "s32", "3888x2592"],
+        S16x9 = 0x2, "16:9-S", ["169-s", "169s", "s-169", "s169", "3888x2184"],
+        S1x1 = 0x3, "1:1-S", ["11-s", "11s", "s-11", "s11", "2592x2592"],
+        S4x3 = 0xa, "4:3-S", ["43-s", "43s", "s-43", "s43", "3456x2592"],
+        S5x4 = 0xc, "5:4-S", ["54-s", "54s", "s-54", "s54", "3264x2592"],
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter)]
+pub enum AspectRatio {
+    R3x2,
+    R16x9,
+    R1x1,
+    R4x3,
+    R5x4,
+}
+
+impl fmt::Display for AspectRatio {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::R3x2 => "3:2",
+            Self::R16x9 => "16:9",
+            Self::R1x1 => "1:1",
+            Self::R4x3 => "4:3",
+            Self::R5x4 => "5:4",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl FromStr for AspectRatio {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let input: String = s
+            .trim()
+            .chars()
+            .filter(|c| c.is_ascii_digit() || matches!(c, ':' | 'x' | 'X' | '/'))
+            .map(|c| if c.is_ascii_digit() { c } else { ':' })
+            .collect();
+
+        match input.as_str() {
+            "3:2" => Ok(Self::R3x2),
+            "16:9" => Ok(Self::R16x9),
+            "1:1" => Ok(Self::R1x1),
+            "4:3" => Ok(Self::R4x3),
+            "5:4" => Ok(Self::R5x4),
+            _ => {
+                if let Some(best) = Self::closest(s) {
+                    bail!("Unknown AspectRatio '{s}'. Did you mean '{best}'?");
+                }
+                bail!("Unknown AspectRatio '{s}'");
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter)]
+pub enum ImageSizeClass {
+    L,
+    M,
+    S,
+}
+
+impl fmt::Display for ImageSizeClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::L => "L",
+            Self::M => "M",
+            Self::S => "S",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl FromStr for ImageSizeClass {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.clean().as_str() {
+            "l" | "large" => Ok(Self::L),
+            "m" | "medium" => Ok(Self::M),
+            "s" | "small" => Ok(Self::S),
+            _ => bail!("Unknown ImageSizeClass '{s}'"),
+        }
+    }
+}
+
+// Pixel dimensions depend on the sensor, so recipes refer to sizes by aspect ratio and class.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, SerializeDisplay, DeserializeFromStr)]
+pub struct CanonicalImageSize {
+    pub aspect_ratio: AspectRatio,
+    pub class: ImageSizeClass,
+}
+
+impl CanonicalImageSize {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        ImageSizeClass::iter().flat_map(|class| {
+            AspectRatio::iter().map(move |aspect_ratio| Self {
+                aspect_ratio,
+                class,
+            })
+        })
+    }
+}
+
+impl fmt::Display for CanonicalImageSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.aspect_ratio, self.class)
+    }
+}
+
+impl FromStr for CanonicalImageSize {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let parts: Vec<&str> = s
+            .trim()
+            .split(['-', ' ', '_'])
+            .filter(|p| !p.is_empty())
+            .collect();
+
+        if let [first, second] = parts.as_slice() {
+            if let (Ok(aspect_ratio), Ok(class)) = (first.parse(), second.parse()) {
+                return Ok(Self {
+                    aspect_ratio,
+                    class,
+                });
+            }
+            if let (Ok(class), Ok(aspect_ratio)) = (first.parse(), second.parse()) {
+                return Ok(Self {
+                    aspect_ratio,
+                    class,
+                });
+            }
+        }
+
+        // 40MP pixel dimensions, as written by older simulation files
+        if let Ok(size) = s.parse::<ImageSize>() {
+            return Ok(size.into());
+        }
+
+        if let Some(best) = crate::input::closest(s, Self::iter().map(|v| v.to_string())) {
+            bail!("Unknown ImageSize '{s}'. Did you mean '{best}'?");
+        }
+
+        bail!("Unknown ImageSize '{s}'");
+    }
+}
+
+impl From<ImageSize> for CanonicalImageSize {
+    fn from(value: ImageSize) -> Self {
+        let (aspect_ratio, class) = match value {
+            ImageSize::L3x2 => (AspectRatio::R3x2, ImageSizeClass::L),
+            ImageSize::L16x9 => (AspectRatio::R16x9, ImageSizeClass::L),
+            ImageSize::L1x1 => (AspectRatio::R1x1, ImageSizeClass::L),
+            ImageSize::L4x3 => (AspectRatio::R4x3, ImageSizeClass::L),
+            ImageSize::L5x4 => (AspectRatio::R5x4, ImageSizeClass::L),
+            ImageSize::M3x2 => (AspectRatio::R3x2, ImageSizeClass::M),
+            ImageSize::M16x9 => (AspectRatio::R16x9, ImageSizeClass::M),
+            ImageSize::M1x1 => (AspectRatio::R1x1, ImageSizeClass::M),
+            ImageSize::M4x3 => (AspectRatio::R4x3, ImageSizeClass::M),
+            ImageSize::M5x4 => (AspectRatio::R5x4, ImageSizeClass::M),
+            ImageSize::S3x2 => (AspectRatio::R3x2, ImageSizeClass::S),
+            ImageSize::S16x9 => (AspectRatio::R16x9, ImageSizeClass::S),
+            ImageSize::S1x1 => (AspectRatio::R1x1, ImageSizeClass::S),
+            ImageSize::S4x3 => (AspectRatio::R4x3, ImageSizeClass::S),
+            ImageSize::S5x4 => (AspectRatio::R5x4, ImageSizeClass::S),
+        };
+
+        Self {
+            aspect_ratio,
+            class,
+        }
+    }
+}
+
+impl ImageSize {
+    // The same size class in 3:2, which every body supports.
+    pub fn fallback(self) -> Option<Self> {
+        let canonical = CanonicalImageSize::from(self);
+        if canonical.aspect_ratio == AspectRatio::R3x2 {
+            return None;
+        }
+
+        Some(Self::from(CanonicalImageSize {
+            aspect_ratio: AspectRatio::R3x2,
+            class: canonical.class,
+        }))
+    }
+}
+
+impl From<CanonicalImageSize> for ImageSize {
+    fn from(value: CanonicalImageSize) -> Self {
+        match (value.aspect_ratio, value.class) {
+            (AspectRatio::R3x2, ImageSizeClass::L) => Self::L3x2,
+            (AspectRatio::R16x9, ImageSizeClass::L) => Self::L16x9,
+            (AspectRatio::R1x1, ImageSizeClass::L) => Self::L1x1,
+            (AspectRatio::R4x3, ImageSizeClass::L) => Self::L4x3,
+            (AspectRatio::R5x4, ImageSizeClass::L) => Self::L5x4,
+            (AspectRatio::R3x2, ImageSizeClass::M) => Self::M3x2,
+            (AspectRatio::R16x9, ImageSizeClass::M) => Self::M16x9,
+            (AspectRatio::R1x1, ImageSizeClass::M) => Self::M1x1,
+            (AspectRatio::R4x3, ImageSizeClass::M) => Self::M4x3,
+            (AspectRatio::R5x4, ImageSizeClass::M) => Self::M5x4,
+            (AspectRatio::R3x2, ImageSizeClass::S) => Self::S3x2,
+            (AspectRatio::R16x9, ImageSizeClass::S) => Self::S16x9,
+            (AspectRatio::R1x1, ImageSizeClass::S) => Self::S1x1,
+            (AspectRatio::R4x3, ImageSizeClass::S) => Self::S4x3,
+            (AspectRatio::R5x4, ImageSizeClass::S) => Self::S5x4,
+        }
+    }
+}
+
+impl Serialize for ImageSize {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        CanonicalImageSize::from(*self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ImageSize {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        CanonicalImageSize::deserialize(deserializer).map(Into::into)
     }
 }
 
@@ -543,6 +796,22 @@ impl FilmSimulation {
                 | Self::AcrosG
         )
     }
+
+    // The closest simulation available on older bodies, if any.
+    pub const fn fallback(self) -> Option<Self> {
+        match self {
+            Self::RealaAce => Some(Self::Provia),
+            Self::NostalgicNegative => Some(Self::ClassicNegative),
+            Self::ClassicNegative => Some(Self::ClassicChrome),
+            Self::EternaBleachBypass => Some(Self::Eterna),
+            Self::Eterna | Self::ClassicChrome => Some(Self::PRONegStd),
+            Self::AcrosSTD => Some(Self::Monochrome),
+            Self::AcrosYe => Some(Self::MonochromeYe),
+            Self::AcrosR => Some(Self::MonochromeR),
+            Self::AcrosG => Some(Self::MonochromeG),
+            _ => None,
+        }
+    }
 }
 fuji_enum! {
     #[derive(SerializeDisplay, DeserializeFromStr)]
@@ -869,6 +1138,15 @@ impl FromStr for NoiseReduction {
     }
 }
 
+impl Choices for NoiseReduction {
+    fn choices() -> Vec<String> {
+        (-4..=4)
+            .filter_map(|value| Self::try_from_int(value).ok())
+            .map(|value| value.to_string())
+            .collect()
+    }
+}
+
 impl Serialize for NoiseReduction {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -930,13 +1208,7 @@ impl BackupObjectInfo {
 }
 
 impl PtpSerialize for BackupObjectInfo {
-    fn try_into_ptp(&self) -> io::Result<Vec<u8>> {
-        let mut buf = Vec::new();
-        self.try_write_ptp(&mut buf)?;
-        Ok(buf)
-    }
-
-    fn try_write_ptp(&self, buf: &mut Vec<u8>) -> io::Result<()> {
+    fn try_write_ptp<W: ptp_cursor::Write + ?Sized>(&self, buf: &mut W) -> io::Result<()> {
         let object_info = ObjectInfo {
             object_format: ObjectFormat::FujiBackup,
             compressed_size: self.compressed_size,
@@ -3,18 +3,27 @@ pub mod error;
 pub mod fuji;
 pub mod props;
 pub mod structs;
+pub mod write;
 
 pub use container::*;
 pub use props::*;
 pub use structs::*;
+pub use write::PropWrite;
 
-use std::{cmp::min, io::Cursor, time::Duration};
+use std::{cmp::min, fmt, io::Cursor, time::Duration};
 
 use anyhow::anyhow;
-use log::{debug, error, trace, warn};
-use ptp_cursor::{PtpDeserialize, PtpSerialize};
+use log::{Level, debug, error, info, log, log_enabled, trace, warn};
+use ptp_cursor::{DecodeLimits, PtpDeserialize, PtpLayout, PtpSerialize, annotated_hex_dump};
 use rusb::GlobalContext;
 
+// Where a property value is read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropSource {
+    Current,
+    FactoryDefault,
+}
+
 pub struct Ptp {
     pub bus: u8,
     pub address: u8,
@@ -24,6 +33,8 @@ pub struct Ptp {
     pub handle: rusb::DeviceHandle<GlobalContext>,
     pub transaction_id: u32,
     pub chunk_size: usize,
+    pub limits: DecodeLimits,
+    pub dry_run: bool,
 }
 
 impl Ptp {
@@ -33,6 +44,23 @@ impl Ptp {
         params: &[u32],
         data: Option<&[u8]>,
     ) -> anyhow::Result<Vec<u8>> {
+        if self.dry_run && code.is_write() && !is_selector_write(code, params) {
+            info!(
+                "Dry run: would send {code:?} with parameters {params:?} ({} bytes)",
+                data.map_or(0, <[u8]>::len)
+            );
+            if let Some(data) = data
+                && matches!(
+                    code,
+                    CommandCode::SendObjectInfo | CommandCode::FujiSendObjectInfo
+                )
+                && let Ok(info) = ObjectInfo::try_from_ptp_with_limits(data, self.limits)
+            {
+                info!("Dry run: object info {info:?}");
+            }
+            return Ok(Vec::new());
+        }
+
         let transaction_id = self.transaction_id;
 
         trace!(
@@ -147,7 +175,18 @@ impl Ptp {
         trace!("PTP read: initial chunk ({n} bytes)");
 
         let mut cur = Cursor::new(buf);
-        let container_info = ContainerInfo::try_read_ptp(&mut cur)?;
+        let container_info = ContainerInfo::try_read_ptp(&mut cur).map_err(error::Error::from)?;
+
+        let total_len = container_info.total_len as usize;
+        if total_len < ContainerInfo::SIZE {
+            return Err(anyhow!(error::Error::Malformed(format!(
+                "Container length {total_len} is smaller than its header ({} bytes)",
+                ContainerInfo::SIZE
+            ))));
+        }
+        self.limits
+            .check_container_size(total_len)
+            .map_err(error::Error::from)?;
 
         let payload_len = container_info.payload_len();
         if payload_len == 0 {
@@ -190,7 +229,44 @@ impl Ptp {
     pub fn get_info(&mut self) -> anyhow::Result<DeviceInfo> {
         debug!("Retrieving device info");
         let response = self.send(CommandCode::GetDeviceInfo, &[], None)?;
-        let info = DeviceInfo::try_from_ptp(&response)?;
+        let info = DeviceInfo::try_from_ptp_with_limits(&response, self.limits)
+            .map_err(error::Error::from)?;
+        Ok(info)
+    }
+
+    pub fn get_prop_desc(&mut self, code: u32) -> anyhow::Result<DevicePropDesc> {
+        debug!("Getting device prop desc: 0x{code:04x}");
+        let response = self.send(CommandCode::GetDevicePropDesc, &[code], None)?;
+        let desc = DevicePropDesc::try_from_ptp_with_limits(&response, self.limits)
+            .map_err(error::Error::from)?;
+        Ok(desc)
+    }
+
+    pub fn get_object_prop_desc(
+        &mut self,
+        code: u32,
+        format: u32,
+    ) -> anyhow::Result<ObjectPropDesc> {
+        debug!("Getting object prop desc: 0x{code:04x} (format 0x{format:04x})");
+        let response = self.send(CommandCode::GetObjectPropDesc, &[code, format], None)?;
+        let desc = ObjectPropDesc::try_from_ptp_with_limits(&response, self.limits)
+            .map_err(error::Error::from)?;
+        Ok(desc)
+    }
+
+    pub fn get_storage_ids(&mut self) -> anyhow::Result<Vec<u32>> {
+        debug!("Getting storage IDs");
+        let response = self.send(CommandCode::GetStorageIDs, &[], None)?;
+        let ids = <Vec<u32>>::try_from_ptp_with_limits(&response, self.limits)
+            .map_err(error::Error::from)?;
+        Ok(ids)
+    }
+
+    pub fn get_storage_info(&mut self, storage_id: u32) -> anyhow::Result<StorageInfo> {
+        debug!("Getting storage info: 0x{storage_id:08x}");
+        let response = self.send(CommandCode::GetStorageInfo, &[storage_id], None)?;
+        let info = StorageInfo::try_from_ptp_with_limits(&response, self.limits)
+            .map_err(error::Error::from)?;
         Ok(info)
     }
 
@@ -208,21 +284,81 @@ impl Ptp {
 
     pub fn get_prop<T: PtpDeserialize>(&mut self, code: DevicePropCode) -> anyhow::Result<T> {
         let bytes = self.get_prop_raw(code)?;
-        let value = T::try_from_ptp(&bytes)?;
+        let value = T::try_from_ptp_with_limits(&bytes, self.limits).map_err(error::Error::from)?;
+        Ok(value)
+    }
+
+    pub fn get_prop_default<T: PtpDeserialize>(
+        &mut self,
+        code: DevicePropCode,
+    ) -> anyhow::Result<T> {
+        let desc = self.get_prop_desc(code.into())?;
+        let bytes = desc.factory_default.try_into_ptp()?;
+        let value = T::try_from_ptp_with_limits(&bytes, self.limits).map_err(error::Error::from)?;
         Ok(value)
     }
 
-    pub fn set_prop<T: PtpSerialize>(
+    pub fn read_prop<T: PtpDeserialize>(
+        &mut self,
+        code: DevicePropCode,
+        source: PropSource,
+    ) -> anyhow::Result<T> {
+        match source {
+            PropSource::Current => self.get_prop(code),
+            PropSource::FactoryDefault => self.get_prop_default(code),
+        }
+    }
+
+    pub fn set_prop<T: PtpSerialize + fmt::Debug>(
         &mut self,
         code: DevicePropCode,
         value: &T,
     ) -> anyhow::Result<()> {
+        if self.dry_run && !code.is_selector() {
+            info!("Dry run: would set {code:?} = {value:?}");
+            return Ok(());
+        }
+
         let bytes = value.try_into_ptp()?;
         self.set_prop_raw(code, &bytes)?;
+
+        // Every read of a dry run depends on the selection, so make sure the camera switched.
+        if self.dry_run && self.get_prop_raw(code)? != bytes {
+            return Err(anyhow!(
+                "Dry run: camera did not select {code:?} = {value:?}"
+            ));
+        }
+
         Ok(())
     }
 }
 
+// Selector writes only choose what subsequent reads address, so dry runs still send them.
+fn is_selector_write(code: CommandCode, params: &[u32]) -> bool {
+    code == CommandCode::SetDevicePropValue
+        && params
+            .first()
+            .and_then(|&prop| DevicePropCode::try_from(prop).ok())
+            .is_some_and(DevicePropCode::is_selector)
+}
+
+pub fn trace_layout<T: PtpLayout>(label: &str, buf: &[u8]) {
+    log_layout::<T>(Level::Trace, label, buf);
+}
+
+// Logs an annotated hex dump of `buf`, only decoding its layout when `level` is enabled.
+pub fn log_layout<T: PtpLayout>(level: Level, label: &str, buf: &[u8]) {
+    if log_enabled!(level) {
+        let layout = T::ptp_layout(buf);
+        log!(
+            level,
+            "{label} ({} bytes):\n{}",
+            buf.len(),
+            annotated_hex_dump(buf, &layout)
+        );
+    }
+}
+
 impl Drop for Ptp {
     fn drop(&mut self) {
         if let Err(e) = self.handle.release_interface(self.interface) {
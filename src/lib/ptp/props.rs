@@ -37,3 +37,10 @@ pub enum DevicePropCode {
     // TODO: 0xD1A5 All 7s
     FujiBatteryInfo2 = 0xD36B,
 }
+
+impl DevicePropCode {
+    // Properties that only choose what subsequent reads address, so dry runs still set them.
+    pub const fn is_selector(self) -> bool {
+        matches!(self, Self::FujiCustomSetting)
+    }
+}
@@ -1,7 +1,13 @@
+use std::{
+    fmt,
+    io::{self, Cursor},
+};
+
 use num_enum::{IntoPrimitive, TryFromPrimitive};
-use ptp_macro::{PtpDeserialize, PtpSerialize};
+use ptp_cursor::{DataType, PtpDeserialize, PtpValue, Read};
+use ptp_macro::{PtpDeserialize, PtpLayout, PtpSerialize};
 
-#[derive(Debug, PtpSerialize, PtpDeserialize)]
+#[derive(Debug, PtpSerialize, PtpDeserialize, PtpLayout)]
 pub struct DeviceInfo {
     pub version: u16,
     pub vendor_ex_id: u32,
@@ -39,7 +45,7 @@ pub enum ObjectFormat {
     FujiRAF = 0xf802,
 }
 
-#[derive(Debug, Clone, Default, PtpSerialize, PtpDeserialize)]
+#[derive(Debug, Clone, Default, PtpSerialize, PtpDeserialize, PtpLayout)]
 pub struct ObjectInfo {
     pub storage_id: u32,
     pub object_format: ObjectFormat,
@@ -61,3 +67,176 @@ pub struct ObjectInfo {
     pub date_modified: String,
     pub keywords: String,
 }
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PropForm {
+    None,
+    Range {
+        min: PtpValue,
+        max: PtpValue,
+        step: PtpValue,
+    },
+    Enumeration(Vec<PtpValue>),
+    DateTime,
+    FixedLengthArray(u16),
+    RegularExpression(String),
+    ByteArray(u32),
+    LongString(u32),
+}
+
+impl PropForm {
+    fn try_read_ptp_as<R: Read>(cur: &mut R, data_type: DataType) -> io::Result<Self> {
+        let flag = cur.read_ptp_u8()?;
+        let form = match flag {
+            0x00 => Self::None,
+            0x01 => Self::Range {
+                min: PtpValue::try_read_ptp_as(cur, data_type)?,
+                max: PtpValue::try_read_ptp_as(cur, data_type)?,
+                step: PtpValue::try_read_ptp_as(cur, data_type)?,
+            },
+            0x02 => {
+                let len = cur.read_ptp_u16()? as usize;
+                cur.limits().check_array_len(len)?;
+                let values = (0..len)
+                    .map(|_| PtpValue::try_read_ptp_as(cur, data_type))
+                    .collect::<io::Result<_>>()?;
+                Self::Enumeration(values)
+            }
+            0x03 => Self::DateTime,
+            0x04 => Self::FixedLengthArray(cur.read_ptp_u16()?),
+            0x05 => Self::RegularExpression(cur.read_ptp_str()?),
+            0x06 => Self::ByteArray(cur.read_ptp_u32()?),
+            0xFF => Self::LongString(cur.read_ptp_u32()?),
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Unknown form flag 0x{flag:02x}"),
+                ));
+            }
+        };
+        Ok(form)
+    }
+}
+
+impl fmt::Display for PropForm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::None => Ok(()),
+            Self::Range { min, max, step } => write!(f, "range {min}..={max} step {step}"),
+            Self::Enumeration(values) => {
+                let values = values
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(f, "one of [{values}]")
+            }
+            Self::DateTime => write!(f, "date time"),
+            Self::FixedLengthArray(len) => write!(f, "fixed length {len}"),
+            Self::RegularExpression(regex) => write!(f, "matching {regex:?}"),
+            Self::ByteArray(len) => write!(f, "byte array up to {len}"),
+            Self::LongString(len) => write!(f, "long string up to {len}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DevicePropDesc {
+    pub code: u16,
+    pub data_type: DataType,
+    pub writable: bool,
+    pub factory_default: PtpValue,
+    pub current: PtpValue,
+    pub form: PropForm,
+}
+
+impl PtpDeserialize for DevicePropDesc {
+    fn try_from_ptp(buf: &[u8]) -> io::Result<Self> {
+        let mut cur = Cursor::new(buf);
+        let value = Self::try_read_ptp(&mut cur)?;
+        cur.expect_end()?;
+        Ok(value)
+    }
+
+    fn try_read_ptp<R: Read>(cur: &mut R) -> io::Result<Self> {
+        let code = cur.read_ptp_u16()?;
+        let data_type = DataType::try_read_ptp(cur)?;
+        let writable = cur.read_ptp_u8()? == 0x01;
+        let factory_default = PtpValue::try_read_ptp_as(cur, data_type)?;
+        let current = PtpValue::try_read_ptp_as(cur, data_type)?;
+        let form = PropForm::try_read_ptp_as(cur, data_type)?;
+
+        Ok(Self {
+            code,
+            data_type,
+            writable,
+            factory_default,
+            current,
+            form,
+        })
+    }
+}
+
+impl fmt::Display for DevicePropDesc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let access = if self.writable { "rw" } else { "ro" };
+        write!(
+            f,
+            "0x{:04x} {} {access} = {} (default {})",
+            self.code, self.data_type, self.current, self.factory_default
+        )?;
+        if self.form != PropForm::None {
+            write!(f, ", {}", self.form)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ObjectPropDesc {
+    pub code: u16,
+    pub data_type: DataType,
+    pub writable: bool,
+    pub factory_default: PtpValue,
+    pub group_code: u32,
+    pub form: PropForm,
+}
+
+impl PtpDeserialize for ObjectPropDesc {
+    fn try_from_ptp(buf: &[u8]) -> io::Result<Self> {
+        let mut cur = Cursor::new(buf);
+        let value = Self::try_read_ptp(&mut cur)?;
+        cur.expect_end()?;
+        Ok(value)
+    }
+
+    fn try_read_ptp<R: Read>(cur: &mut R) -> io::Result<Self> {
+        let code = cur.read_ptp_u16()?;
+        let data_type = DataType::try_read_ptp(cur)?;
+        let writable = cur.read_ptp_u8()? == 0x01;
+        let factory_default = PtpValue::try_read_ptp_as(cur, data_type)?;
+        let group_code = cur.read_ptp_u32()?;
+        let form = PropForm::try_read_ptp_as(cur, data_type)?;
+
+        Ok(Self {
+            code,
+            data_type,
+            writable,
+            factory_default,
+            group_code,
+            form,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PtpSerialize, PtpDeserialize, PtpLayout)]
+pub struct StorageInfo {
+    pub storage_type: u16,
+    pub filesystem_type: u16,
+    pub access_capability: u16,
+    pub max_capacity: u64,
+    pub free_space_in_bytes: u64,
+    pub free_space_in_images: u32,
+    pub storage_description: String,
+    pub volume_label: String,
+}
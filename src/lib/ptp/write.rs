@@ -0,0 +1,72 @@
+use std::fmt;
+
+use log::info;
+use ptp_cursor::{DecodeLimits, PtpDeserialize, PtpSerialize};
+
+use crate::ptp::{DevicePropCode, Ptp};
+
+// A single device property write, kept encoded alongside its human-readable value.
+#[derive(Debug, Clone)]
+pub struct PropWrite {
+    pub code: DevicePropCode,
+    pub value: Vec<u8>,
+    pub display: String,
+    decode: fn(&[u8], DecodeLimits) -> Option<String>,
+}
+
+fn decode<T: PtpDeserialize + fmt::Display>(buf: &[u8], limits: DecodeLimits) -> Option<String> {
+    T::try_from_ptp_with_limits(buf, limits)
+        .ok()
+        .map(|value| value.to_string())
+}
+
+impl PropWrite {
+    pub fn new<T>(code: DevicePropCode, value: &T) -> anyhow::Result<Self>
+    where
+        T: PtpSerialize + PtpDeserialize + fmt::Display,
+    {
+        Ok(Self {
+            code,
+            value: value.try_into_ptp()?,
+            display: value.to_string(),
+            decode: decode::<T>,
+        })
+    }
+
+    pub fn decode(&self, buf: &[u8], limits: DecodeLimits) -> String {
+        (self.decode)(buf, limits).unwrap_or_else(|| format!("{buf:02x?}"))
+    }
+}
+
+impl fmt::Display for PropWrite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} = {}", self.code, self.display)
+    }
+}
+
+impl Ptp {
+    pub fn write_prop(&mut self, write: &PropWrite) -> anyhow::Result<()> {
+        if self.dry_run {
+            info!("Dry run: would set {write}");
+            return Ok(());
+        }
+
+        self.set_prop_raw(write.code, &write.value)?;
+        Ok(())
+    }
+
+    // Reads the property back, returning what the camera reports if it differs from the write.
+    pub fn verify_prop(&mut self, write: &PropWrite) -> anyhow::Result<Option<String>> {
+        let actual = self.get_prop_raw(write.code)?;
+        if actual == write.value {
+            return Ok(None);
+        }
+
+        let actual = write.decode(&actual, self.limits);
+        if actual == write.display {
+            Ok(None)
+        } else {
+            Ok(Some(actual))
+        }
+    }
+}
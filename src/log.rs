@@ -6,8 +6,10 @@ use log4rs::{
     encode::pattern::PatternEncoder,
 };
 
-pub fn init(verbose: u8) -> anyhow::Result<()> {
+pub fn init(verbose: u8, dry_run: bool) -> anyhow::Result<()> {
     let level = match verbose {
+        // NOTE: Dry runs report the skipped writes at info level.
+        0 if dry_run => LevelFilter::Info,
         0 => LevelFilter::Warn,
         1 => LevelFilter::Info,
         2 => LevelFilter::Debug,
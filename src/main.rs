@@ -11,10 +11,11 @@ fn main() -> anyhow::Result<()> {
     let cli = cli::Cli::parse();
     let options = cli.options;
 
-    log::init(options.verbose)?;
+    log::init(options.verbose, options.dry_run)?;
 
     match cli.command {
         Commands::Device(device_cmd) => cli::device::handle(device_cmd, options)?,
+        Commands::Recipe(recipe_cmd) => cli::recipe::handle(recipe_cmd, options)?,
         Commands::Backup(backup_cmd) => cli::backup::handle(backup_cmd, options)?,
         Commands::Simulation(simulation_cmd) => {
             cli::simulation::handle(simulation_cmd, options)?;